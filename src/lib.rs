@@ -1,4 +1,7 @@
-use std::f32::consts::PI;
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::PI,
+};
 
 // Add libraries from the cimvr_engine_interface crate
 use cimvr_engine_interface::{make_app_state, pcg::Pcg, pkg_namespace, prelude::*, FrameTime};
@@ -23,67 +26,399 @@ use serde::{Deserialize, Serialize};
 const WITDH: f32 = 80.;
 const HEIGHT: f32 = 120.;
 
+// Create some constant values for the Playfield (where the window sits in the world)
+// These are set at build time: the client scales the meshes by the playfield when it uploads them,
+// so moving, rotating or scaling the cabinet needs a rebuild of both the server and the client
+const PLAYFIELD_POSITION: Vec3 = Vec3::ZERO;
+const PLAYFIELD_ROTATION: Quat = Quat::IDENTITY;
+const PLAYFIELD_SCALE: f32 = 1.; // How big one playfield unit is drawn in the world (for a scaled cabinet)
+const PLAYFIELD_SPAWN_MARGIN: f32 = 10.; // How far from the bottom/top of the window the player/enemy spawns
+
 // Create some constant values for Enemy
 const ENEMY_COUNT: u32 = 2;
 const ENEMY_MAX_BULLET: u32 = 5;
 const ENEMY_SPAWN_TIME: f32 = 0.5;
 const ENEMY_BULLET_SPEED: f32 = 100.;
 const ENEMY_SPEED: f32 = 50.;
-const ENEMY_SIZE: f32 = 3.; // Because of the obj file, this value is not used (update this value after changing the obj size)
+const ENEMY_HITBOX_SCALE: f32 = 1.0; // Scale of the hitbox compared to the obj file bounds
+const ENEMY_FIRE_COOLDOWN: f32 = 0.0;
+const ENEMY_HEALTH: f32 = 1.0;
+const ENEMY_BULLET_DAMAGE: f32 = 1.0;
+const ENEMY_INDESTRUCTIBLE_BULLET_CHANCE: f32 = 0.2;
+const ENEMY_DIVE_SPEED: f32 = 40.;
+const ENEMY_DIVE_CHANCE: f32 = 0.2; // Chance per second that an enemy in the formation starts a dive
+const BOSS_CHANCE: f32 = 0.2; // Chance that a spawned enemy is a boss
+const BOSS_HEALTH: f32 = 2.0;
+const BOSS_MAX_ESCORTS: usize = 2; // How many enemies from the formation a diving boss takes with it
 
 // Create some constant values for Player
 const PLAYER_SPAWN_TIME: f32 = 3.0;
 const PLAYER_BULLET_SPEED: f32 = 100.;
 const PLAYER_SPEED: f32 = 100.;
-const PLAYER_SIZE: f32 = 3.; // Because of the obj file, this value is not used (update this value after changing the obj size)
+const PLAYER_HITBOX_SCALE: f32 = 0.8; // Scale of the hitbox compared to the obj file bounds (smaller is more forgiving)
+const PLAYER_MAX_BULLET: u32 = u32::MAX;
+const PLAYER_FIRE_COOLDOWN: f32 = 0.0;
+const PLAYER_BULLET_DAMAGE: f32 = 1.0;
+
+// Create some constant values for the Number Display
+const NUMBER_DISPLAY_MAX_DIGITS: usize = 10; // Enough for any u32 value
+const SCORE_DIGIT_SPACING: f32 = 5.;
+
+// Create some constant values for the HUD layout
+const HUD_MARGIN: f32 = 4.; // How far inside the window edges the HUD regions are anchored
+const HUD_LINE_SPACING: f32 = 5.; // The distance between the lines of text in one HUD region
+
+// Create some constant values for the Text Label
+const TEXT_LABEL_MAX_LENGTH: usize = 32;
+const FONT_WIDTH: f32 = 4.; // The size of the font grid that the glyph strokes are drawn on
+const FONT_HEIGHT: f32 = 6.;
+const FONT_ADVANCE: f32 = 6.; // How far along the font grid the next glyph starts
+const SCORE_LABEL_SIZE: f32 = 3.;
+
+// Create some constant values for the Game
+const STAGE_KILLS: u32 = 10; // How many enemies the player destroys to move on to the next stage
+const HIGH_SCORE_COUNT: usize = 10;
+const HIGH_SCORE_LABEL_SIZE: f32 = 2.5;
+const HIGH_SCORE_ROW_SPACING: f32 = 4.;
+const COMBO_WINDOW: f32 = 2.; // Seconds after a kill that the next kill keeps the combo chain going
+const COMBO_MAX_MULTIPLIER: u32 = 8;
+const START_LIVES: u32 = 2; // The ships in reserve at the start of a game, besides the one being played
+const EXTRA_LIFE_FIRST: u32 = 20_000; // The score of the first bonus ship
+const EXTRA_LIFE_EVERY: u32 = 70_000; // The score between the bonus ships after the first one
+const EXTRA_LIFE_NOTICE_TIME: f32 = 3.; // Seconds the bonus ship notification is shown
+const RESERVE_SHIP_SCALE: f32 = 0.5; // Size of the reserve ship icons compared to the player ship
+const RESERVE_SHIP_SPACING: f32 = 5.;
+const RESERVE_SHIP_MAX: u32 = 8; // The most reserve ships that are drawn
+const STAGE_FLAG_SPACING: f32 = 3.;
+const STAGE_FLAG_MAX: u32 = 10; // The most stage flags that are drawn
+const RESULTS_SCREEN_TIME: f32 = 10.; // Seconds the results are shown at game over, unless fire is pressed first
+
+// Create some constant values for the Score Popup
+const POPUP_LIFETIME: f32 = 1.2;
+const POPUP_BLINK_TIME: f32 = 0.5; // The popup blinks out over its last seconds
+const POPUP_SPEED: f32 = 8.; // How fast the popup drifts upward
+const POPUP_SIZE: f32 = 2.;
+const POPUP_BIG_SIZE: f32 = 3.5;
+const POPUP_BIG_POINTS: u32 = 400; // Popups of this many points or more use the big size
 
 // Create some constant values for Bullet
 const BULLET_SIZE: f32 = 0.5;
+const BULLET_DESPAWN_INSET: f32 = 2.5; // How far inside the top and bottom window edges the bullets are removed
+
+// Server simulation constants
+const FIXED_TIMESTEP: f32 = 1. / 60.; // Every gameplay tick moves the game forward by this many seconds, no matter the frame rate
+const MAX_TICKS_PER_FRAME: u32 = 5; // The most ticks a slow frame catches up on; past that the game slows down instead
+
+// Create some constant values for Weapon
+const WEAPON_MAX_MUZZLES: usize = 4;
+
+// Create the collision layers; each collider is on one layer and has a mask of the layers it collides with
+const LAYER_PLAYER: u32 = 1 << 0;
+const LAYER_ENEMY: u32 = 1 << 1;
+const LAYER_PLAYER_BULLET: u32 = 1 << 2;
+const LAYER_ENEMY_BULLET: u32 = 1 << 3;
+
+// Create some constant values for the collision broadphase
+const GRID_CELL_SIZE: f32 = 10.;
+
+// Create some constant values for Missile
+const MISSILE_SIZE: f32 = 1.;
+const MISSILE_SPEED: f32 = 60.;
+const MISSILE_TURN_RATE: f32 = PI; // How fast the missile can turn toward the target (radians per second)
+const MISSILE_LIFETIME: f32 = 3.;
+const MISSILE_MAX_BULLET: u32 = 2;
+const MISSILE_FIRE_COOLDOWN: f32 = 0.5;
+const MISSILE_DAMAGE: f32 = 1.0;
+
+// Create some constant values for Laser
+const LASER_DAMAGE: f32 = 4.0; // Damage per second while the beam touches the enemy
+const LASER_ENERGY: f32 = 2.0; // Seconds of firing with a full energy meter
+const LASER_ENERGY_DRAIN: f32 = 1.0;
+const LASER_ENERGY_RECHARGE: f32 = 0.5;
+const LASER_SEGMENT_LENGTH: f32 = 1.0;
+const ENERGY_METER_WIDTH: f32 = 20.0;
+const ENERGY_METER_SEGMENTS: u32 = 20;
+const ENERGY_METER_GAP: f32 = 2.5; // How far below the bottom window edge the energy meter is drawn
+
+// Create some constant values for Autofire
+const KEYBOARD_AUTOFIRE: bool = false;
+const GAMEPAD_AUTOFIRE: bool = true;
+const AUTOFIRE_RATE: f32 = 8.; // Shots per second while fire is held
 
 // All state associated with client-side behaviour
 #[derive(Default)]
 struct ClientState {
     input: InputHelper,
+    switch_weapon_held: bool,
+    keyboard_fire: Autofire,
+    gamepad_fire: Autofire,
+    autofire_toggle_held: bool,
+    // The glyph meshes (character, size and color) that were already sent
+    uploaded_glyphs: HashSet<(u8, u32, [u32; 3])>,
+}
+
+// Autofire setting and timer for one input device
+#[derive(Default)]
+struct Autofire {
+    // When enabled, holding fire keeps firing at the rate; otherwise only fresh presses fire
+    enabled: bool,
+    rate: f32,
+    timer: f32,
+    held: bool,
+}
+
+// Implement the autofire timer
+impl Autofire {
+    fn new(enabled: bool, rate: f32) -> Self {
+        Self {
+            enabled,
+            rate,
+            ..Default::default()
+        }
+    }
+
+    // Update the fire button state of this frame and return true if a shot should be fired
+    fn update(&mut self, held: bool, delta: f32) -> bool {
+        // Check if the fire button was just pressed on this frame
+        let pressed = held && !self.held;
+        self.held = held;
+
+        // A fresh press always fires and restarts the timer
+        if pressed {
+            self.timer = 1. / self.rate;
+            return true;
+        }
+
+        // If autofire is on and fire is still held, fire every time the timer runs out
+        if self.enabled && held {
+            self.timer -= delta;
+            if self.timer <= 0. {
+                self.timer += 1. / self.rate;
+                return true;
+            }
+        }
+
+        false
+    }
 }
 
-// Add movement command as message from client to server
+// Add movement command as message from client to server; this is the direction the player wants to move in
 #[derive(Message, Serialize, Deserialize)]
 #[locality("Remote")]
 struct MoveCommand(Vec3);
 
-// Add fire command as a message from client to server
+// Add fire command as a message from client to server; this is sent while fire is held and is true when a shot should be fired
 #[derive(Message, Serialize, Deserialize)]
 #[locality("Remote")]
 struct FireCommand(bool);
 
+// Add switch weapon command as a message from client to server
+#[derive(Message, Serialize, Deserialize)]
+#[locality("Remote")]
+struct SwitchWeaponCommand;
+
+// What kind of entity took part in a hit
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HitKind {
+    Player,
+    Enemy(EnemyKind),
+    PlayerBullet,
+    EnemyBullet,
+    PlayerLaser,
+}
+
+// Add hit event as a message inside the server side; it is sent for every hit so that scoring, effects, stats and sounds can react to it
+#[derive(Message, Serialize, Deserialize, Copy, Clone)]
+#[locality("Local")]
+pub struct HitEvent {
+    // The entity that got hit and what kind it is
+    pub target: EntityId,
+    pub target_kind: HitKind,
+    // The entity that hit it and what kind it is
+    pub source: EntityId,
+    pub source_kind: HitKind,
+    // Whether the target was destroyed by the hit
+    pub destroyed: bool,
+    // The points the hit is worth before the combo multiplier
+    pub points: u32,
+    // Where the target was inside the playfield when it got hit
+    pub position: Vec3,
+}
+
+// Add score event as a message inside the server side; it is sent for every kill with the points that were added to the score
+#[derive(Message, Serialize, Deserialize, Copy, Clone)]
+#[locality("Local")]
+pub struct ScoreEvent {
+    // The points added, with the combo multiplier at the time of the kill
+    pub points: u32,
+    // Where the kill was inside the playfield
+    pub position: Vec3,
+}
+
 // Add Player Component
-#[derive(Component, Serialize, Deserialize, Copy, Clone)]
-pub struct Player {
-    pub current_position: Vec3,
+#[derive(Component, Serialize, Deserialize, Copy, Clone, Default)]
+pub struct Player;
+
+// What kind of enemy it is
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EnemyKind {
+    Bee,
+    Butterfly,
+    Boss,
 }
 
-// Implement Default for Player Component
-impl Default for Player {
-    fn default() -> Self {
-        Self {
-            current_position: Vec3::new(0.0, -50.0, 0.0),
-        }
-    }
+// What the enemy is doing at the moment
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EnemyState {
+    // Moving around in the formation at the top of the window
+    Formation,
+    // Diving down toward the player
+    Diving,
 }
 
 // Add Enemy Component
 #[derive(Component, Serialize, Deserialize, Copy, Clone)]
 pub struct Enemy {
-    pub current_position: Vec3,
-    pub bullet_count: u32,
+    pub health: f32,
+    pub kind: EnemyKind,
+    pub state: EnemyState,
+    // The boss this enemy dives with, if it is escorting one
+    pub leader: EntityId,
+    pub escorting: bool,
 }
 
 // Implement Default for Enemy Component
 impl Default for Enemy {
     fn default() -> Self {
+        Self::new(EnemyKind::Bee)
+    }
+}
+
+// Implement the Enemy constructor
+impl Enemy {
+    pub fn new(kind: EnemyKind) -> Self {
         Self {
-            current_position: Vec3::new(0.0, 50.0, 0.0),
-            bullet_count: 0,
+            health: match kind {
+                EnemyKind::Boss => BOSS_HEALTH,
+                _ => ENEMY_HEALTH,
+            },
+            kind,
+            state: EnemyState::Formation,
+            leader: EntityId(0),
+            escorting: false,
+        }
+    }
+}
+
+// Scoring table: the points for destroying an enemy, by its kind and what it was doing when it died
+// (the designers can adjust these values)
+#[derive(Copy, Clone)]
+pub struct ScoringTable {
+    // The points for an enemy destroyed in the formation, by kind (bee, butterfly, boss)
+    pub formation: [u32; 3],
+    // The points for an enemy destroyed while diving, by kind
+    pub diving: [u32; 3],
+    // The points for a diving boss, by how many of its escorts are still diving with it
+    pub boss_escorts: [u32; BOSS_MAX_ESCORTS + 1],
+}
+
+// Implement Default for ScoringTable
+impl Default for ScoringTable {
+    fn default() -> Self {
+        Self {
+            formation: [50, 80, 150],
+            diving: [100, 160, 400],
+            boss_escorts: [400, 800, 1600],
+        }
+    }
+}
+
+// Implement the ScoringTable functions
+impl ScoringTable {
+    // The points for destroying the enemy, where escorts is how many enemies are diving with it
+    pub fn points(&self, enemy: &Enemy, escorts: usize) -> u32 {
+        match (enemy.state, enemy.kind) {
+            (EnemyState::Formation, kind) => self.formation[kind as usize],
+            (EnemyState::Diving, EnemyKind::Boss) => {
+                self.boss_escorts[escorts.min(BOSS_MAX_ESCORTS)]
+            }
+            (EnemyState::Diving, kind) => self.diving[kind as usize],
+        }
+    }
+}
+
+// Add Velocity Component; every entity that moves has one, and only the integration system moves the LocalTransform
+#[derive(Component, Serialize, Deserialize, Copy, Clone, Default)]
+pub struct Velocity(pub Vec3);
+
+// Add LocalTransform Component; the position and rotation inside the playfield, which all the gameplay works with
+// (the Transform that gets drawn is placed from it by the playfield system)
+#[derive(Component, Serialize, Deserialize, Copy, Clone, Default)]
+pub struct LocalTransform(pub Transform);
+
+// Playfield resource: where the window sits in the world, how big it is and how big it is drawn
+// (the gameplay is centered on the playfield origin, so moving, rotating or scaling it does not change the gameplay)
+#[derive(Copy, Clone)]
+pub struct Playfield {
+    pub transform: Transform,
+    pub size: Vec3,
+    pub scale: f32,
+}
+
+// The playfield that both the server and the client use, so that the meshes the client makes match the gameplay
+// (it does not change while the game runs)
+const PLAYFIELD: Playfield = Playfield {
+    transform: Transform {
+        pos: PLAYFIELD_POSITION,
+        orient: PLAYFIELD_ROTATION,
+    },
+    size: Vec3::new(WITDH, HEIGHT, 0.),
+    scale: PLAYFIELD_SCALE,
+};
+
+// Implement Default for Playfield
+impl Default for Playfield {
+    fn default() -> Self {
+        PLAYFIELD
+    }
+}
+
+// Implement the Playfield functions
+impl Playfield {
+    // Place a transform inside the playfield into the world
+    pub fn to_world(&self, local: Transform) -> Transform {
+        Transform {
+            pos: self.transform.pos + self.transform.orient * (local.pos * self.scale),
+            orient: self.transform.orient * local.orient,
+        }
+    }
+
+    // Scale the mesh from the playfield units to the world, the same way as the positions
+    pub fn scale_mesh(&self, mut mesh: Mesh) -> Mesh {
+        mesh.vertices
+            .iter_mut()
+            .for_each(|v| v.pos = (Vec3::from(v.pos) * self.scale).to_array());
+        mesh
+    }
+
+    // Where the player spawns: the bottom middle of the window
+    pub fn player_spawn(&self) -> Vec3 {
+        Vec3::new(0., -self.size.y / 2. + PLAYFIELD_SPAWN_MARGIN, 0.)
+    }
+
+    // Where the enemy spawns: the top middle of the window
+    pub fn enemy_spawn(&self) -> Vec3 {
+        Vec3::new(0., self.size.y / 2. - PLAYFIELD_SPAWN_MARGIN, 0.)
+    }
+
+    // Where the HUD region is anchored inside the window
+    pub fn hud_anchor(&self, region: HudRegion) -> Vec3 {
+        let half = self.size / 2.;
+        match region {
+            HudRegion::TopScore => Vec3::new(-half.x / 2., half.y - HUD_MARGIN, 0.),
+            HudRegion::HighScore => Vec3::new(half.x / 2., half.y - HUD_MARGIN, 0.),
+            HudRegion::ReserveShips => Vec3::new(-half.x + HUD_MARGIN, -half.y + HUD_MARGIN, 0.),
+            HudRegion::StageFlags => Vec3::new(half.x - HUD_MARGIN, -half.y + HUD_MARGIN, 0.),
         }
     }
 }
@@ -91,22 +426,383 @@ impl Default for Enemy {
 // Add Bullet Component
 #[derive(Component, Serialize, Deserialize, Copy, Clone)]
 pub struct Bullet {
-    from_player: bool,
-    from_enemy: bool,
+    // The entity that fired the bullet (the collider layer tells whether it is a player or an enemy bullet)
     entity_id: EntityId,
+    damage: f32,
+    indestructible: bool,
 }
 
 // Implement Default for Bullet Component
 impl Default for Bullet {
     fn default() -> Self {
         Self {
-            from_player: false,
-            from_enemy: false,
             entity_id: EntityId(0),
+            damage: 0.0,
+            indestructible: false,
+        }
+    }
+}
+
+// Add Collider Shape; this decides how the hitbox of the collider is checked
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+pub enum ColliderShape {
+    // An axis aligned rectangle hitbox where the size is the width and the height
+    Rectangle,
+    // A round hitbox that fits inside the size
+    Circle,
+    // A rectangle with round ends along its longer side that fits inside the size (a tall one for tall objects)
+    Capsule,
+}
+
+// Add Collider Component; every entity that can hit or be hit has one
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct Collider {
+    pub shape: ColliderShape,
+    pub size: Vec3,
+    // The hitbox center from the entity position
+    pub offset: Vec3,
+    // The layer this collider is on and the layers it collides with
+    pub layer: u32,
+    pub mask: u32,
+}
+
+// Implement the collider presets for each kind of entity
+impl Collider {
+    // The player collider uses the hitbox from the player obj file, with round ends so that the corners are forgiving
+    pub fn player(hitbox: Hitbox) -> Self {
+        Self {
+            shape: ColliderShape::Capsule,
+            size: hitbox.size,
+            offset: hitbox.offset,
+            layer: LAYER_PLAYER,
+            mask: LAYER_ENEMY_BULLET,
+        }
+    }
+
+    // The enemy collider uses the hitbox from the enemy obj file, with round ends like the player
+    pub fn enemy(hitbox: Hitbox) -> Self {
+        Self {
+            shape: ColliderShape::Capsule,
+            size: hitbox.size,
+            offset: hitbox.offset,
+            layer: LAYER_ENEMY,
+            mask: LAYER_PLAYER_BULLET,
+        }
+    }
+
+    // The bullet colliders are round like the bullets
+    pub fn player_bullet(size: f32) -> Self {
+        Self {
+            shape: ColliderShape::Circle,
+            size: Vec3::new(size, size, 0.),
+            offset: Vec3::ZERO,
+            layer: LAYER_PLAYER_BULLET,
+            mask: LAYER_ENEMY | LAYER_ENEMY_BULLET,
+        }
+    }
+
+    pub fn enemy_bullet(size: f32) -> Self {
+        Self {
+            shape: ColliderShape::Circle,
+            size: Vec3::new(size, size, 0.),
+            offset: Vec3::ZERO,
+            layer: LAYER_ENEMY_BULLET,
+            mask: LAYER_PLAYER | LAYER_PLAYER_BULLET,
+        }
+    }
+
+    // Split the hitbox into a rectangle core (as half the width and height) and the radius of the round edge around it,
+    // so that every pair of shapes can be checked the same way
+    pub fn rounded_box(&self) -> (Vec3, f32) {
+        let half_size = self.size / 2.;
+        match self.shape {
+            // A rectangle has no round edge
+            ColliderShape::Rectangle => (half_size, 0.0),
+            // A circle is only the round edge around its center
+            ColliderShape::Circle => (Vec3::ZERO, half_size.x.min(half_size.y)),
+            // A capsule is a line along its longer side with the round edge around it
+            ColliderShape::Capsule => {
+                let radius = half_size.x.min(half_size.y);
+                (
+                    Vec3::new(half_size.x - radius, half_size.y - radius, 0.),
+                    radius,
+                )
+            }
+        }
+    }
+}
+
+// Hitbox computed from the bounds of a mesh
+#[derive(Copy, Clone)]
+pub struct Hitbox {
+    pub size: Vec3,
+    pub offset: Vec3,
+}
+
+// Implement the hitbox computation
+impl Hitbox {
+    // Compute the hitbox from the mesh bounds after the rotation the entity uses, scaled around its center
+    pub fn from_mesh(mesh: &Mesh, rotation: Quat, scale: f32) -> Self {
+        // An empty mesh has no hitbox
+        if mesh.vertices.is_empty() {
+            return Self {
+                size: Vec3::ZERO,
+                offset: Vec3::ZERO,
+            };
+        }
+
+        // Find the bottom left and the top right corners of the rotated mesh
+        let mut min = Vec3::splat(f32::MAX);
+        let mut max = Vec3::splat(f32::MIN);
+        for vertex in &mesh.vertices {
+            let position = rotation * Vec3::from(vertex.pos);
+            min = min.min(position);
+            max = max.max(position);
+        }
+
+        Self {
+            size: (max - min) * scale,
+            offset: (min + max) / 2.,
+        }
+    }
+
+    // The point on the hitbox edge from the entity position, where x and y go from -1 (left/bottom) to 1 (right/top)
+    pub fn edge(&self, x: f32, y: f32) -> Vec3 {
+        self.offset + self.size / 2. * Vec3::new(x, y, 0.)
+    }
+}
+
+// Add Weapon Kind; this decides what kind of bullet the weapon fires
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+pub enum WeaponKind {
+    // Fire bullets that fly straight
+    Blaster,
+    // Fire missiles that home in on the nearest enemy
+    Missile,
+    // Fire a continuous beam while the trigger is held
+    Laser,
+}
+
+// Implement the order in which the player cycles through the weapons
+impl WeaponKind {
+    pub fn next(self) -> Self {
+        match self {
+            WeaponKind::Blaster => WeaponKind::Missile,
+            WeaponKind::Missile => WeaponKind::Laser,
+            WeaponKind::Laser => WeaponKind::Blaster,
+        }
+    }
+}
+
+// Add Weapon Pattern; this decides which muzzles fire when the trigger is pulled
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+pub enum WeaponPattern {
+    // Fire from every muzzle at once
+    Volley,
+    // Fire from one muzzle at a time, cycling through the muzzles
+    Alternate,
+}
+
+// Add Weapon Component; this is shared by the player and the enemy so that both fire bullets the same way
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub pattern: WeaponPattern,
+    // The muzzle offsets from the owner position (only the first muzzle_count offsets are used)
+    pub muzzles: [Vec3; WEAPON_MAX_MUZZLES],
+    pub muzzle_count: u32,
+    pub next_muzzle: u32,
+    // The direction and the speed of the bullets
+    pub direction: Vec3,
+    pub bullet_speed: f32,
+    pub bullet_mesh: MeshHandle,
+    pub bullet_collider: Collider,
+    // The damage of each bullet (damage per second for the laser)
+    pub damage: f32,
+    // The chance for each bullet to be indestructible so that player bullets can not cancel it, and how it looks
+    pub indestructible_chance: f32,
+    pub indestructible_mesh: MeshHandle,
+    // The time between two shots and the time left until the next shot
+    pub cooldown: f32,
+    pub cooldown_timer: f32,
+    // The max bullet count on screen from this weapon and the current bullet count on screen
+    pub ammo_cap: u32,
+    pub bullet_count: u32,
+    pub from_player: bool,
+    // Set by the player/enemy fire systems when the weapon should fire on this frame
+    pub trigger: bool,
+    // The energy left in the meter and the energy of a full meter (only used by the laser; every player weapon
+    // holds the laser energy so that it is carried over the weapon switches)
+    pub energy: f32,
+    pub energy_max: f32,
+}
+
+// Implement the weapon presets for the player and the enemy
+impl Weapon {
+    // The player weapon fires two bullets upward from the top left and top right of the ship hitbox
+    pub fn player(hitbox: Hitbox) -> Self {
+        Self {
+            kind: WeaponKind::Blaster,
+            pattern: WeaponPattern::Volley,
+            muzzles: [
+                hitbox.edge(-1., 1.),
+                hitbox.edge(1., 1.),
+                Vec3::ZERO,
+                Vec3::ZERO,
+            ],
+            muzzle_count: 2,
+            next_muzzle: 0,
+            direction: Vec3::new(0.0, 1.0, 0.0),
+            bullet_speed: PLAYER_BULLET_SPEED,
+            bullet_mesh: PLAYER_BULLET_HANDLE,
+            bullet_collider: Collider::player_bullet(BULLET_SIZE),
+            damage: PLAYER_BULLET_DAMAGE,
+            indestructible_chance: 0.0,
+            indestructible_mesh: PLAYER_BULLET_HANDLE,
+            cooldown: PLAYER_FIRE_COOLDOWN,
+            cooldown_timer: 0.0,
+            ammo_cap: PLAYER_MAX_BULLET,
+            bullet_count: 0,
+            from_player: true,
+            trigger: false,
+            energy: LASER_ENERGY,
+            energy_max: LASER_ENERGY,
+        }
+    }
+
+    // The player missile launcher fires one missile at a time from the top left and top right of the ship hitbox
+    pub fn player_missile(hitbox: Hitbox) -> Self {
+        Self {
+            kind: WeaponKind::Missile,
+            pattern: WeaponPattern::Alternate,
+            muzzles: [
+                hitbox.edge(-1., 1.),
+                hitbox.edge(1., 1.),
+                Vec3::ZERO,
+                Vec3::ZERO,
+            ],
+            muzzle_count: 2,
+            next_muzzle: 0,
+            direction: Vec3::new(0.0, 1.0, 0.0),
+            bullet_speed: MISSILE_SPEED,
+            bullet_mesh: MISSILE_HANDLE,
+            bullet_collider: Collider::player_bullet(MISSILE_SIZE),
+            damage: MISSILE_DAMAGE,
+            indestructible_chance: 0.0,
+            indestructible_mesh: MISSILE_HANDLE,
+            cooldown: MISSILE_FIRE_COOLDOWN,
+            cooldown_timer: 0.0,
+            ammo_cap: MISSILE_MAX_BULLET,
+            bullet_count: 0,
+            from_player: true,
+            trigger: false,
+            energy: LASER_ENERGY,
+            energy_max: LASER_ENERGY,
+        }
+    }
+
+    // The player laser fires a beam from the top middle of the ship hitbox while it has energy
+    pub fn player_laser(hitbox: Hitbox) -> Self {
+        Self {
+            kind: WeaponKind::Laser,
+            pattern: WeaponPattern::Volley,
+            muzzles: [hitbox.edge(0., 1.), Vec3::ZERO, Vec3::ZERO, Vec3::ZERO],
+            muzzle_count: 1,
+            next_muzzle: 0,
+            direction: Vec3::new(0.0, 1.0, 0.0),
+            bullet_speed: 0.0,
+            bullet_mesh: LASER_BEAM_HANDLE,
+            bullet_collider: Collider::player_bullet(0.0),
+            damage: LASER_DAMAGE,
+            indestructible_chance: 0.0,
+            indestructible_mesh: LASER_BEAM_HANDLE,
+            cooldown: 0.0,
+            cooldown_timer: 0.0,
+            ammo_cap: 0,
+            bullet_count: 0,
+            from_player: true,
+            trigger: false,
+            energy: LASER_ENERGY,
+            energy_max: LASER_ENERGY,
+        }
+    }
+
+    // The player weapon for each weapon kind
+    pub fn player_kind(kind: WeaponKind, hitbox: Hitbox) -> Self {
+        match kind {
+            WeaponKind::Blaster => Self::player(hitbox),
+            WeaponKind::Missile => Self::player_missile(hitbox),
+            WeaponKind::Laser => Self::player_laser(hitbox),
+        }
+    }
+
+    // The enemy weapon fires one bullet downward from the top of the enemy hitbox (bottom based on player persepective)
+    pub fn enemy(hitbox: Hitbox) -> Self {
+        Self {
+            kind: WeaponKind::Blaster,
+            pattern: WeaponPattern::Volley,
+            muzzles: [hitbox.edge(0., -1.), Vec3::ZERO, Vec3::ZERO, Vec3::ZERO],
+            muzzle_count: 1,
+            next_muzzle: 0,
+            direction: Vec3::new(0.0, -1.0, 0.0),
+            bullet_speed: ENEMY_BULLET_SPEED,
+            bullet_mesh: ENEMY_BULLET_HANDLE,
+            bullet_collider: Collider::enemy_bullet(BULLET_SIZE),
+            damage: ENEMY_BULLET_DAMAGE,
+            indestructible_chance: ENEMY_INDESTRUCTIBLE_BULLET_CHANCE,
+            indestructible_mesh: INDESTRUCTIBLE_BULLET_HANDLE,
+            cooldown: ENEMY_FIRE_COOLDOWN,
+            cooldown_timer: 0.0,
+            ammo_cap: ENEMY_MAX_BULLET,
+            bullet_count: 0,
+            from_player: false,
+            trigger: false,
+            energy: 0.0,
+            energy_max: 0.0,
+        }
+    }
+}
+
+// Add Missile Component; this is added next to the Bullet component for the bullets that home in on the enemy
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct Missile {
+    pub target: EntityId,
+    pub has_target: bool,
+    pub turn_rate: f32,
+}
+
+// Implement Default for Missile Component
+impl Default for Missile {
+    fn default() -> Self {
+        Self {
+            target: EntityId(0),
+            has_target: false,
+            turn_rate: MISSILE_TURN_RATE,
         }
     }
 }
 
+// Add Lifetime Component; the entity is removed once this many seconds have passed
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct Lifetime(pub f32);
+
+// Add Despawn Outside Playfield Component; the entity is removed once it goes this far inside the top or bottom window edge,
+// or past the left or right window edge (a negative value lets it go that far outside the top or bottom first)
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct DespawnOutsidePlayfield(pub f32);
+
+// Add Laser Beam Component; this is the beam entity drawn while the laser of the owner is firing
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct LaserBeam {
+    pub owner: EntityId,
+}
+
+// Add Energy Meter Component; this is the meter entity drawn while the owner has the laser
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct EnergyMeter {
+    pub owner: EntityId,
+}
+
 // Add Player Status Component; this is used as a spwan timer for Player
 #[derive(Component, Serialize, Deserialize, Copy, Clone)]
 pub struct PlayerStatus {
@@ -132,86 +828,491 @@ pub struct EnemyStatus(f32);
 #[derive(Component, Serialize, Deserialize, Copy, Clone)]
 pub struct Score {
     pub score: u32,
-    pub second_digit: u32,
-    pub first_digit: u32,
-    pub second_digit_entity: EntityId,
-    pub first_digit_entity: EntityId,
+    // The combo chain multiplier for the points, and the seconds left for the next kill to keep the chain going
+    pub multiplier: u32,
+    pub combo_timer: f32,
 }
 
+// Implement Default for Score Component
 impl Default for Score {
     fn default() -> Self {
         Self {
             score: 0,
-            second_digit: 10,
-            first_digit: 10,
-            second_digit_entity: EntityId(0),
-            first_digit_entity: EntityId(0),
+            multiplier: 1,
+            combo_timer: 0.0,
         }
     }
 }
 
-// Create mesh handleer based on each object's name
-const PLAYER_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Player"));
-const ENEMY_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Enemy"));
-const PLAYER_BULLET_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Player Bullet"));
-const ENEMY_BULLET_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Enemy Bullet"));
-const WINDOW_SIZE_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Window Size"));
+// Add Combo Label Component; this marks the text label that shows the combo chain multiplier
+#[derive(Component, Serialize, Deserialize, Copy, Clone, Default)]
+pub struct ComboLabel;
+
+// Add Game Phase; this decides what the game is doing right now
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GamePhase {
+    // The player is playing
+    Playing,
+    // The game is over and the shooting results of the game are shown
+    Results,
+    // The game is over and the player is entering the initials for the high score table
+    EnterInitials,
+    // The game is over and the high score table is shown until fire is pressed
+    Attract,
+}
 
-const ZERO_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Zero Text"));
-const ONE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("One Text"));
-const TWO_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Two Text"));
-const THREE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Three Text"));
-const FOUR_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Four Text"));
-const FIVE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Five Text"));
-const SIX_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Six Text"));
-const SEVEN_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Seven Text"));
-const EIGHT_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Eight Text"));
-const NINE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Nine Text"));
+// Add Game State Component; there is one game state entity for the whole game
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct GameState {
+    pub phase: GamePhase,
+    pub stage: u32,
+    // The enemies destroyed on this game, which move the game on to the next stage
+    pub kills: u32,
+    // The initials being entered, and which letter of them is being changed
+    pub initials: [u8; 3],
+    pub cursor: u32,
+    // The ships left in reserve, and how many bonus ships were given on this game
+    pub lives: u32,
+    pub extra_lives: u32,
+    // Seconds left to show the bonus ship notification
+    pub extra_life_notice: f32,
+    // Seconds left to show the results screen
+    pub results_timer: f32,
+    // The shooting statistics of this game
+    pub stats: GameStats,
+}
 
-// Create Meshes for each object
+// Implement Default for Game State Component
+impl Default for GameState {
+    fn default() -> Self {
+        Self {
+            phase: GamePhase::Playing,
+            stage: 1,
+            kills: 0,
+            initials: [b'A'; 3],
+            cursor: 0,
+            lives: START_LIVES,
+            extra_lives: 0,
+            extra_life_notice: 0.0,
+            results_timer: 0.0,
+            stats: GameStats::default(),
+        }
+    }
+}
 
-// Create the Player Mesh --> This is commented out because we are using obj file
-// fn player() -> Mesh {
-//     let size: f32 = PLAYER_SIZE;
+// The shooting statistics of one game
+// (the laser touches the enemy on many ticks, so only bullets and missiles count as shots and hits)
+#[derive(Serialize, Deserialize, Copy, Clone, Default)]
+pub struct GameStats {
+    pub shots: u32,
+    pub hits: u32,
+    // The enemies destroyed by kind (bee, butterfly, boss)
+    pub kills: [u32; 3],
+    // The seconds the game was played
+    pub time: f32,
+}
 
-//     let vertices = vec![
-//         Vertex::new([-size, -size, 0.0], [0.0, 0.0, 1.0]), // Vertex 0
-//         Vertex::new([size, -size, 0.0], [0.0, 0.0, 1.0]),  // Vertex 1
-//         Vertex::new([size, size, 0.0], [0.0, 0.0, 1.0]),   // Vertex 2
-//         Vertex::new([-size, size, 0.0], [0.0, 0.0, 1.0]),  // Vertex 3
-//     ];
+// Implement the GameStats functions
+impl GameStats {
+    // Record the hit if it was the player hitting an enemy
+    pub fn record(&mut self, hit: &HitEvent) {
+        if let HitKind::Enemy(kind) = hit.target_kind {
+            if hit.source_kind == HitKind::PlayerBullet {
+                self.hits += 1;
+            }
+            if hit.destroyed {
+                self.kills[kind as usize] += 1;
+            }
+        }
+    }
 
-//     let indices: Vec<u32> = vec![3, 0, 2, 1, 2, 0];
+    // The percentage of the shots that hit an enemy
+    pub fn hit_ratio(&self) -> f32 {
+        if self.shots == 0 {
+            return 0.0;
+        }
+        self.hits as f32 / self.shots as f32 * 100.
+    }
+}
 
-//     Mesh { vertices, indices }
-// }
+// The scores where a bonus ship is given (the designers can adjust these values)
+#[derive(Copy, Clone)]
+pub struct ExtraLifeTable {
+    // The score of the first bonus ship
+    pub first: u32,
+    // The score between the bonus ships after the first one (0 gives only the first one)
+    pub every: u32,
+}
 
-// // Create the Enemy Mesh --> This is commented out because we are using obj file
-// fn enemy() -> Mesh {
-//     let size: f32 = ENEMY_SIZE;
+// Implement Default for ExtraLifeTable
+impl Default for ExtraLifeTable {
+    fn default() -> Self {
+        Self {
+            first: EXTRA_LIFE_FIRST,
+            every: EXTRA_LIFE_EVERY,
+        }
+    }
+}
 
-//     let vertices = vec![
-//         Vertex::new([-size, -size, 0.0], [1.0, 0.0, 0.0]), // Vertex 0
-//         Vertex::new([size, -size, 0.0], [1.0, 0.0, 0.0]),  // Vertex 1
-//         Vertex::new([size, size, 0.0], [1.0, 0.0, 0.0]),   // Vertex 2
-//         Vertex::new([-size, size, 0.0], [1.0, 0.0, 0.0]),  // Vertex 3
-//     ];
+// Implement the ExtraLifeTable functions
+impl ExtraLifeTable {
+    // The score of the next bonus ship after this many were given, if there is one
+    pub fn threshold(&self, given: u32) -> Option<u32> {
+        match given {
+            0 => Some(self.first),
+            _ if self.every == 0 => None,
+            _ => self.first.checked_add(self.every.checked_mul(given)?),
+        }
+    }
 
-//     let indices: Vec<u32> = vec![3, 0, 2, 1, 2, 0];
+    // How many bonus ships the score has earned on one game
+    pub fn earned(&self, score: u32) -> u32 {
+        if score < self.first {
+            return 0;
+        }
+        // The thresholds after the first one are evenly spaced, so count them without going through them
+        match self.every {
+            0 => 1,
+            every => ((score - self.first) / every).saturating_add(1),
+        }
+    }
+}
 
-//     Mesh { vertices, indices }
-// }
+// The named regions of the HUD, which are anchored to the playfield bounds
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HudRegion {
+    // The score of the player, on the top left
+    TopScore,
+    // The best score, on the top right
+    HighScore,
+    // The ships in reserve, on the bottom left
+    ReserveShips,
+    // The stage flags, on the bottom right
+    StageFlags,
+}
 
-// Create Player Bullet Mesh as a sqaure green
-fn player_bullet() -> Mesh {
-    let size: f32 = BULLET_SIZE;
+// Add HUD Anchor Component; the entity is placed at the offset from its region of the playfield when it is created
+// (the playfield is set at build time, so the regions do not move while the game runs)
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct HudAnchor {
+    pub region: HudRegion,
+    pub offset: Vec3,
+}
 
-    let vertices = vec![
-        Vertex::new([-size, -size, 0.0], [0.0, 1.0, 0.0]),
-        Vertex::new([size, -size, 0.0], [0.0, 1.0, 0.0]),
-        Vertex::new([size, size, 0.0], [0.0, 1.0, 0.0]),
-        Vertex::new([-size, size, 0.0], [0.0, 1.0, 0.0]),
-    ];
+// Add HUD Icon Component; this marks one of a row of icons in a HUD region (the reserve ships or the stage flags)
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct HudIcon {
+    // Which icon of the row it is, from the region anchor
+    pub index: u32,
+}
+
+// Add High Score Display Component; this marks the number display that shows the best score
+#[derive(Component, Serialize, Deserialize, Copy, Clone, Default)]
+pub struct HighScoreDisplay;
+
+// Add Score Popup Component; this is the short-lived text label that shows the points a kill was worth
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct ScorePopup {
+    pub points: u32,
+}
+
+// Add Extra Life Label Component; this marks the text label that shows the bonus ship notification
+#[derive(Component, Serialize, Deserialize, Copy, Clone, Default)]
+pub struct ExtraLifeLabel;
+
+// One row of the high score table
+#[derive(Serialize, Deserialize, Copy, Clone)]
+pub struct HighScoreEntry {
+    pub initials: [u8; 3],
+    pub score: u32,
+    pub stage: u32,
+}
+
+// Add High Score Table Component; the best scores from the highest down
+// (it lives on its own entity instead of the server state, so that it is kept when the plugin reloads as long as the engine keeps the entities;
+// nothing else stores it, so the table starts empty again when the entities are gone too)
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct HighScoreTable {
+    pub entries: [HighScoreEntry; HIGH_SCORE_COUNT],
+    pub count: u32,
+}
+
+// Implement Default for High Score Table Component
+impl Default for HighScoreTable {
+    fn default() -> Self {
+        Self {
+            entries: [HighScoreEntry {
+                initials: [b'A'; 3],
+                score: 0,
+                stage: 0,
+            }; HIGH_SCORE_COUNT],
+            count: 0,
+        }
+    }
+}
+
+// Implement the High Score Table functions
+impl HighScoreTable {
+    // The rows that are filled in
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries[..self.count as usize]
+    }
+
+    // Whether the score is good enough to get on the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries().len() < HIGH_SCORE_COUNT
+                || self.entries().iter().any(|entry| score > entry.score))
+    }
+
+    // Put the entry on the table below the scores that are as high or higher, pushing the lowest one off a full table
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        let index = self
+            .entries()
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.count as usize);
+        if index >= HIGH_SCORE_COUNT {
+            return;
+        }
+        self.count = (self.count + 1).min(HIGH_SCORE_COUNT as u32);
+        self.entries[index..self.count as usize].rotate_right(1);
+        self.entries[index] = entry;
+    }
+}
+
+// Add Text Anchor; this decides which side of the text sits on the display position
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+pub enum TextAnchor {
+    Left,
+    Center,
+    Right,
+}
+
+// Add Number Display Component; this draws the value with one digit entity per digit
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct NumberDisplay {
+    pub value: u32,
+    // Where the digits are drawn inside the playfield, and how they line up on it
+    pub position: Vec3,
+    pub alignment: TextAnchor,
+    // The distance between the centers of two digits next to each other
+    pub spacing: f32,
+    // Pad the value with leading zeros up to this many digits (1 for no leading zeros)
+    pub min_digits: u32,
+    // The value the digit entities show right now (None before the first draw)
+    pub shown: Option<u32>,
+    // The glyph entities that are kept around between the draws, and the digit each one shows
+    pub digit_entities: [EntityId; NUMBER_DISPLAY_MAX_DIGITS],
+    pub shown_digits: [u32; NUMBER_DISPLAY_MAX_DIGITS],
+    pub digit_count: u32,
+}
+
+// Implement the Number Display functions
+impl NumberDisplay {
+    pub fn new(position: Vec3, alignment: TextAnchor, spacing: f32, min_digits: u32) -> Self {
+        Self {
+            value: 0,
+            position,
+            alignment,
+            spacing,
+            min_digits,
+            shown: None,
+            digit_entities: [EntityId(0); NUMBER_DISPLAY_MAX_DIGITS],
+            shown_digits: [0; NUMBER_DISPLAY_MAX_DIGITS],
+            digit_count: 0,
+        }
+    }
+
+    // The digits of the value from left to right, padded with the leading zeros
+    pub fn digits(&self) -> ([u32; NUMBER_DISPLAY_MAX_DIGITS], usize) {
+        let mut digits = [0; NUMBER_DISPLAY_MAX_DIGITS];
+        let mut count = 0;
+        let mut value = self.value;
+        // Take the digits from the right until the value runs out and there are enough digits
+        while count < NUMBER_DISPLAY_MAX_DIGITS
+            && (value > 0 || count == 0 || count < self.min_digits as usize)
+        {
+            digits[count] = value % 10;
+            value /= 10;
+            count += 1;
+        }
+        digits[..count].reverse();
+        (digits, count)
+    }
+
+    // Where the center of the digit at the index is drawn
+    pub fn digit_position(&self, index: usize, count: usize) -> Vec3 {
+        let width = (count.max(1) - 1) as f32 * self.spacing;
+        let left = match self.alignment {
+            TextAnchor::Left => self.position.x,
+            TextAnchor::Center => self.position.x - width / 2.,
+            TextAnchor::Right => self.position.x - width,
+        };
+        Vec3::new(
+            left + index as f32 * self.spacing,
+            self.position.y,
+            self.position.z,
+        )
+    }
+}
+
+// Add Glyph Component; this is one character entity drawn for the HUD text of the owner
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct Glyph {
+    pub owner: EntityId,
+}
+
+// Add Text Label Component; this draws the text with one glyph entity per character, from the vector font
+// (the label entity's LocalTransform is where the text is drawn, and the size is the height of the capital letters)
+#[derive(Component, Serialize, Deserialize, Copy, Clone)]
+pub struct TextLabel {
+    pub text: [u8; TEXT_LABEL_MAX_LENGTH],
+    pub length: u32,
+    pub size: f32,
+    pub color: [f32; 3],
+    pub anchor: TextAnchor,
+    // The glyph entities that are kept around between the draws
+    pub glyph_entities: [EntityId; TEXT_LABEL_MAX_LENGTH],
+    pub glyph_count: u32,
+}
+
+// Implement the Text Label functions
+impl TextLabel {
+    pub fn new(text: &str, size: f32, color: [f32; 3], anchor: TextAnchor) -> Self {
+        let mut label = Self {
+            text: [b' '; TEXT_LABEL_MAX_LENGTH],
+            length: 0,
+            size,
+            color,
+            anchor,
+            glyph_entities: [EntityId(0); TEXT_LABEL_MAX_LENGTH],
+            glyph_count: 0,
+        };
+        label.set_text(text);
+        label
+    }
+
+    // Change the text (cut to the max length, and the characters the font does not have are drawn as '?')
+    pub fn set_text(&mut self, text: &str) {
+        self.length = 0;
+        for character in text.chars().take(TEXT_LABEL_MAX_LENGTH) {
+            let character = character.to_ascii_uppercase();
+            self.text[self.length as usize] =
+                if character == ' ' || !font_strokes(character).is_empty() {
+                    character as u8
+                } else {
+                    b'?'
+                };
+            self.length += 1;
+        }
+    }
+
+    // The characters of the text
+    pub fn characters(&self) -> &[u8] {
+        &self.text[..self.length as usize]
+    }
+
+    // Where the bottom left of the glyph at the index is drawn, from the label position (the text is centered vertically)
+    pub fn glyph_offset(&self, index: usize) -> Vec3 {
+        let scale = self.size / FONT_HEIGHT;
+        let width = ((self.length.max(1) - 1) as f32 * FONT_ADVANCE + FONT_WIDTH) * scale;
+        let left = match self.anchor {
+            TextAnchor::Left => 0.,
+            TextAnchor::Center => -width / 2.,
+            TextAnchor::Right => -width,
+        };
+        Vec3::new(
+            left + index as f32 * FONT_ADVANCE * scale,
+            -self.size / 2.,
+            0.,
+        )
+    }
+}
+
+// Create mesh handleer based on each object's name
+const PLAYER_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Player"));
+const RESERVE_SHIP_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Reserve Ship"));
+const STAGE_FLAG_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Stage Flag"));
+const ENEMY_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Enemy"));
+const PLAYER_BULLET_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Player Bullet"));
+const ENEMY_BULLET_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Enemy Bullet"));
+const INDESTRUCTIBLE_BULLET_HANDLE: MeshHandle =
+    MeshHandle::new(pkg_namespace!("Indestructible Bullet"));
+const MISSILE_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Missile"));
+const LASER_BEAM_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Laser Beam"));
+const ENERGY_METER_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Energy Meter"));
+const WINDOW_SIZE_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Window Size"));
+
+const ZERO_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Zero Text"));
+const ONE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("One Text"));
+const TWO_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Two Text"));
+const THREE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Three Text"));
+const FOUR_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Four Text"));
+const FIVE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Five Text"));
+const SIX_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Six Text"));
+const SEVEN_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Seven Text"));
+const EIGHT_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Eight Text"));
+const NINE_TEXT_HANDLE: MeshHandle = MeshHandle::new(pkg_namespace!("Nine Text"));
+
+// The text handle list for the digits, in order from zero to nine
+const DIGIT_HANDLES: [MeshHandle; 10] = [
+    ZERO_TEXT_HANDLE,
+    ONE_TEXT_HANDLE,
+    TWO_TEXT_HANDLE,
+    THREE_TEXT_HANDLE,
+    FOUR_TEXT_HANDLE,
+    FIVE_TEXT_HANDLE,
+    SIX_TEXT_HANDLE,
+    SEVEN_TEXT_HANDLE,
+    EIGHT_TEXT_HANDLE,
+    NINE_TEXT_HANDLE,
+];
+
+// Create Meshes for each object
+
+// Create the Player Mesh --> This is commented out because we are using obj file
+// fn player() -> Mesh {
+//     let size: f32 = PLAYER_SIZE;
+
+//     let vertices = vec![
+//         Vertex::new([-size, -size, 0.0], [0.0, 0.0, 1.0]), // Vertex 0
+//         Vertex::new([size, -size, 0.0], [0.0, 0.0, 1.0]),  // Vertex 1
+//         Vertex::new([size, size, 0.0], [0.0, 0.0, 1.0]),   // Vertex 2
+//         Vertex::new([-size, size, 0.0], [0.0, 0.0, 1.0]),  // Vertex 3
+//     ];
+
+//     let indices: Vec<u32> = vec![3, 0, 2, 1, 2, 0];
+
+//     Mesh { vertices, indices }
+// }
+
+// // Create the Enemy Mesh --> This is commented out because we are using obj file
+// fn enemy() -> Mesh {
+//     let size: f32 = ENEMY_SIZE;
+
+//     let vertices = vec![
+//         Vertex::new([-size, -size, 0.0], [1.0, 0.0, 0.0]), // Vertex 0
+//         Vertex::new([size, -size, 0.0], [1.0, 0.0, 0.0]),  // Vertex 1
+//         Vertex::new([size, size, 0.0], [1.0, 0.0, 0.0]),   // Vertex 2
+//         Vertex::new([-size, size, 0.0], [1.0, 0.0, 0.0]),  // Vertex 3
+//     ];
+
+//     let indices: Vec<u32> = vec![3, 0, 2, 1, 2, 0];
+
+//     Mesh { vertices, indices }
+// }
+
+// Create Player Bullet Mesh as a sqaure green
+fn player_bullet() -> Mesh {
+    let size: f32 = BULLET_SIZE;
+
+    let vertices = vec![
+        Vertex::new([-size, -size, 0.0], [0.0, 1.0, 0.0]),
+        Vertex::new([size, -size, 0.0], [0.0, 1.0, 0.0]),
+        Vertex::new([size, size, 0.0], [0.0, 1.0, 0.0]),
+        Vertex::new([-size, size, 0.0], [0.0, 1.0, 0.0]),
+    ];
 
     let indices: Vec<u32> = vec![3, 0, 2, 1, 2, 0];
 
@@ -234,13 +1335,200 @@ fn enemy_bullet() -> Mesh {
     Mesh { vertices, indices }
 }
 
+// Create Indestructible Bullet Mesh as a sqaure purple
+fn indestructible_bullet() -> Mesh {
+    let size: f32 = BULLET_SIZE;
+
+    let vertices = vec![
+        Vertex::new([-size, -size, 0.0], [1.0, 0.0, 1.0]),
+        Vertex::new([size, -size, 0.0], [1.0, 0.0, 1.0]),
+        Vertex::new([size, size, 0.0], [1.0, 0.0, 1.0]),
+        Vertex::new([-size, size, 0.0], [1.0, 0.0, 1.0]),
+    ];
+
+    let indices: Vec<u32> = vec![3, 0, 2, 1, 2, 0];
+
+    Mesh { vertices, indices }
+}
+
+// Create Missile Mesh as a yellow arrow pointing up
+fn missile() -> Mesh {
+    let size: f32 = MISSILE_SIZE;
+
+    let vertices = vec![
+        Vertex::new([0.0, size, 0.0], [1.0, 1.0, 0.0]),
+        Vertex::new([-size / 2., -size, 0.0], [1.0, 1.0, 0.0]),
+        Vertex::new([size / 2., -size, 0.0], [1.0, 1.0, 0.0]),
+    ];
+
+    let indices: Vec<u32> = vec![0, 1, 2];
+
+    Mesh { vertices, indices }
+}
+
+// Create Laser Beam Mesh as a cyan line going up, split into segments so that only part of it can be drawn
+fn laser_beam(length: f32) -> Mesh {
+    let segments = (length / LASER_SEGMENT_LENGTH) as u32;
+
+    let vertices = (0..=segments)
+        .map(|i| Vertex::new([0.0, i as f32 * LASER_SEGMENT_LENGTH, 0.0], [0.0, 1.0, 1.0]))
+        .collect();
+
+    let indices: Vec<u32> = (0..segments).flat_map(|i| [i, i + 1]).collect();
+
+    Mesh { vertices, indices }
+}
+
+// Create Energy Meter Mesh as a cyan line going right, split into segments so that only part of it can be drawn
+fn energy_meter() -> Mesh {
+    let segment_length = ENERGY_METER_WIDTH / ENERGY_METER_SEGMENTS as f32;
+
+    let vertices = (0..=ENERGY_METER_SEGMENTS)
+        .map(|i| Vertex::new([i as f32 * segment_length, 0.0, 0.0], [0.0, 1.0, 1.0]))
+        .collect();
+
+    let indices: Vec<u32> = (0..ENERGY_METER_SEGMENTS)
+        .flat_map(|i| [i, i + 1])
+        .collect();
+
+    Mesh { vertices, indices }
+}
+
+// Create the mesh of a stage flag: a pole with a flag on its top
+fn stage_flag() -> Mesh {
+    let color = [1.0, 0.5, 0.0];
+    let vertices = vec![
+        Vertex::new([0.0, 0.0, 0.0], color),
+        Vertex::new([0.0, 3.0, 0.0], color),
+        Vertex::new([2.0, 2.25, 0.0], color),
+        Vertex::new([0.0, 1.5, 0.0], color),
+    ];
+
+    let indices: Vec<u32> = vec![0, 1, 1, 2, 2, 3];
+
+    Mesh { vertices, indices }
+}
+
+// The strokes of every character of the vector font, as lines through the points on the font grid
+// (the grid is FONT_WIDTH wide and FONT_HEIGHT tall from the bottom left; space and unknown characters have no strokes)
+fn font_strokes(character: char) -> &'static [&'static [(i8, i8)]] {
+    match character {
+        'A' => &[&[(0, 0), (0, 4), (2, 6), (4, 4), (4, 0)], &[(0, 3), (4, 3)]],
+        'B' => &[
+            &[(0, 0), (0, 6), (3, 6), (4, 5), (4, 4), (3, 3), (0, 3)],
+            &[(3, 3), (4, 2), (4, 1), (3, 0), (0, 0)],
+        ],
+        'C' => &[&[(4, 6), (0, 6), (0, 0), (4, 0)]],
+        'D' => &[&[(0, 0), (0, 6), (2, 6), (4, 4), (4, 2), (2, 0), (0, 0)]],
+        'E' => &[&[(4, 6), (0, 6), (0, 0), (4, 0)], &[(0, 3), (3, 3)]],
+        'F' => &[&[(4, 6), (0, 6), (0, 0)], &[(0, 3), (3, 3)]],
+        'G' => &[&[(4, 6), (0, 6), (0, 0), (4, 0), (4, 3), (2, 3)]],
+        'H' => &[&[(0, 0), (0, 6)], &[(4, 0), (4, 6)], &[(0, 3), (4, 3)]],
+        'I' => &[&[(0, 6), (4, 6)], &[(2, 6), (2, 0)], &[(0, 0), (4, 0)]],
+        'J' => &[&[(4, 6), (4, 0), (0, 0), (0, 2)]],
+        'K' => &[&[(0, 0), (0, 6)], &[(4, 6), (0, 3), (4, 0)]],
+        'L' => &[&[(0, 6), (0, 0), (4, 0)]],
+        'M' => &[&[(0, 0), (0, 6), (2, 4), (4, 6), (4, 0)]],
+        'N' => &[&[(0, 0), (0, 6), (4, 0), (4, 6)]],
+        'O' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)]],
+        'P' => &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3)]],
+        'Q' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)], &[(2, 2), (4, 0)]],
+        'R' => &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3), (4, 0)]],
+        'S' => &[&[
+            (4, 5),
+            (3, 6),
+            (1, 6),
+            (0, 5),
+            (0, 4),
+            (1, 3),
+            (3, 3),
+            (4, 2),
+            (4, 1),
+            (3, 0),
+            (1, 0),
+            (0, 1),
+        ]],
+        'T' => &[&[(0, 6), (4, 6)], &[(2, 6), (2, 0)]],
+        'U' => &[&[(0, 6), (0, 0), (4, 0), (4, 6)]],
+        'V' => &[&[(0, 6), (2, 0), (4, 6)]],
+        'W' => &[&[(0, 6), (1, 0), (2, 3), (3, 0), (4, 6)]],
+        'X' => &[&[(0, 0), (4, 6)], &[(0, 6), (4, 0)]],
+        'Y' => &[&[(0, 6), (2, 3), (4, 6)], &[(2, 3), (2, 0)]],
+        'Z' => &[&[(0, 6), (4, 6), (0, 0), (4, 0)]],
+        '0' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0), (4, 6)]],
+        '1' => &[&[(1, 5), (2, 6), (2, 0)], &[(1, 0), (3, 0)]],
+        '2' => &[&[(0, 6), (4, 6), (4, 3), (0, 3), (0, 0), (4, 0)]],
+        '3' => &[&[(0, 6), (4, 6), (4, 0), (0, 0)], &[(1, 3), (4, 3)]],
+        '4' => &[&[(0, 6), (0, 3), (4, 3)], &[(4, 6), (4, 0)]],
+        '5' => &[&[(4, 6), (0, 6), (0, 3), (4, 3), (4, 0), (0, 0)]],
+        '6' => &[&[(4, 6), (0, 6), (0, 0), (4, 0), (4, 3), (0, 3)]],
+        '7' => &[&[(0, 6), (4, 6), (1, 0)]],
+        '8' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)], &[(0, 3), (4, 3)]],
+        '9' => &[&[(4, 3), (0, 3), (0, 6), (4, 6), (4, 0), (0, 0)]],
+        '.' => &[&[(2, 0), (2, 1)]],
+        ',' => &[&[(2, 1), (1, -1)]],
+        ':' => &[&[(2, 1), (2, 2)], &[(2, 4), (2, 5)]],
+        '!' => &[&[(2, 6), (2, 2)], &[(2, 1), (2, 0)]],
+        '?' => &[
+            &[(0, 5), (1, 6), (3, 6), (4, 5), (4, 4), (2, 3), (2, 2)],
+            &[(2, 1), (2, 0)],
+        ],
+        '-' => &[&[(1, 3), (3, 3)]],
+        '+' => &[&[(1, 3), (3, 3)], &[(2, 2), (2, 4)]],
+        '=' => &[&[(1, 2), (3, 2)], &[(1, 4), (3, 4)]],
+        '/' => &[&[(0, 0), (4, 6)]],
+        '\'' => &[&[(2, 6), (2, 4)]],
+        '(' => &[&[(3, 6), (1, 4), (1, 2), (3, 0)]],
+        ')' => &[&[(1, 6), (3, 4), (3, 2), (1, 0)]],
+        '_' => &[&[(0, 0), (4, 0)]],
+        '%' => &[
+            &[(0, 0), (4, 6)],
+            &[(0, 6), (1, 6), (1, 5), (0, 5), (0, 6)],
+            &[(3, 1), (4, 1), (4, 0), (3, 0), (3, 1)],
+        ],
+        _ => &[],
+    }
+}
+
+// The mesh handle of a glyph; every character, size and color of the font has its own mesh
+fn glyph_handle(character: u8, size: f32, color: [f32; 3]) -> MeshHandle {
+    MeshHandle::new(&format!(
+        "{} {} {} {:?}",
+        pkg_namespace!("Glyph"),
+        character,
+        size,
+        color
+    ))
+}
+
+// Create Glyph Mesh as the strokes of the character, scaled so that the capital letters are the size tall
+fn glyph(character: u8, size: f32, color: [f32; 3]) -> Mesh {
+    let scale = size / FONT_HEIGHT;
+    let mut vertices = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    for stroke in font_strokes(character as char) {
+        // Connect every point of the stroke to the next one
+        let start = vertices.len() as u32;
+        for (x, y) in stroke.iter() {
+            vertices.push(Vertex::new(
+                [*x as f32 * scale, *y as f32 * scale, 0.0],
+                color,
+            ));
+        }
+        indices.extend((start..start + stroke.len() as u32 - 1).flat_map(|i| [i, i + 1]));
+    }
+
+    Mesh { vertices, indices }
+}
+
 // Create Window Mesh so that the users will know what is the limit of movement
-fn window_size() -> Mesh {
+fn window_size(size: Vec3) -> Mesh {
     let vertices = vec![
-        Vertex::new([-WITDH / 2., -HEIGHT / 2., 0.0], [1.; 3]),
-        Vertex::new([WITDH / 2., -HEIGHT / 2., 0.0], [1.; 3]),
-        Vertex::new([WITDH / 2., HEIGHT / 2., 0.0], [1.; 3]),
-        Vertex::new([-WITDH / 2., HEIGHT / 2., 0.0], [1.; 3]),
+        Vertex::new([-size.x / 2., -size.y / 2., 0.0], [1.; 3]),
+        Vertex::new([size.x / 2., -size.y / 2., 0.0], [1.; 3]),
+        Vertex::new([size.x / 2., size.y / 2., 0.0], [1.; 3]),
+        Vertex::new([-size.x / 2., size.y / 2., 0.0], [1.; 3]),
     ];
 
     let indices: Vec<u32> = vec![3, 0, 0, 1, 1, 2, 2, 3];
@@ -276,34 +1564,77 @@ impl UserState for ClientState {
             .iter_mut()
             .for_each(|v| v.uvw = enemy_color);
 
+        // Make the reserve ship icon as a smaller player mesh, already turned to face the camera like the player
+        let reserve_ship_rotation = Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.);
+        let mut reserve_ship_mesh = new_player_mesh.clone();
+        reserve_ship_mesh.vertices.iter_mut().for_each(|v| {
+            v.pos = (reserve_ship_rotation * Vec3::from(v.pos) * RESERVE_SHIP_SCALE).to_array()
+        });
+
         // Send the player mesh and the player mesh handler to the server side
         io.send(&UploadMesh {
             id: PLAYER_HANDLE,
-            mesh: new_player_mesh,
+            mesh: PLAYFIELD.scale_mesh(new_player_mesh),
+        });
+
+        // Send the reserve ship mesh and the reserve ship mesh handler to the server side
+        io.send(&UploadMesh {
+            id: RESERVE_SHIP_HANDLE,
+            mesh: PLAYFIELD.scale_mesh(reserve_ship_mesh),
+        });
+
+        // Send the stage flag mesh and the stage flag mesh handler to the server side
+        io.send(&UploadMesh {
+            id: STAGE_FLAG_HANDLE,
+            mesh: PLAYFIELD.scale_mesh(stage_flag()),
         });
 
         // Send the enemy mesh and the enemy mesh handler to the server side
         io.send(&UploadMesh {
             id: ENEMY_HANDLE,
-            mesh: new_enemy_mesh,
+            mesh: PLAYFIELD.scale_mesh(new_enemy_mesh),
         });
 
         // Send the player bullet mesh and the player bullet mesh handler to the server side
         io.send(&UploadMesh {
             id: PLAYER_BULLET_HANDLE,
-            mesh: player_bullet(),
+            mesh: PLAYFIELD.scale_mesh(player_bullet()),
         });
 
         // Send the enemy bullet mesh and the enemy bullet mesh handler to the server side
         io.send(&UploadMesh {
             id: ENEMY_BULLET_HANDLE,
-            mesh: enemy_bullet(),
+            mesh: PLAYFIELD.scale_mesh(enemy_bullet()),
+        });
+
+        // Send the indestructible bullet mesh and the indestructible bullet mesh handler to the server side
+        io.send(&UploadMesh {
+            id: INDESTRUCTIBLE_BULLET_HANDLE,
+            mesh: PLAYFIELD.scale_mesh(indestructible_bullet()),
+        });
+
+        // Send the missile mesh and the missile mesh handler to the server side
+        io.send(&UploadMesh {
+            id: MISSILE_HANDLE,
+            mesh: PLAYFIELD.scale_mesh(missile()),
+        });
+
+        // Send the laser beam mesh and the laser beam mesh handler to the server side
+        io.send(&UploadMesh {
+            id: LASER_BEAM_HANDLE,
+            mesh: PLAYFIELD.scale_mesh(laser_beam(PLAYFIELD.size.y)),
+        });
+
+        // Send the energy meter mesh and the energy meter mesh handler to the server side
+        io.send(&UploadMesh {
+            id: ENERGY_METER_HANDLE,
+            mesh: PLAYFIELD.scale_mesh(energy_meter()),
         });
 
         // Send the window mesh and the window mesh handler to the server side
         io.send(&UploadMesh {
             id: WINDOW_SIZE_HANDLE,
-            mesh: window_size(),
+            mesh: PLAYFIELD.scale_mesh(window_size(PLAYFIELD.size)),
         });
 
         // Declare the enemy color as faded gray
@@ -318,7 +1649,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: ZERO_TEXT_HANDLE,
-            mesh: zero_text,
+            mesh: PLAYFIELD.scale_mesh(zero_text),
         });
 
         let mut one_text = obj_lines_to_mesh(&include_str!("assets/one.obj"));
@@ -330,7 +1661,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: ONE_TEXT_HANDLE,
-            mesh: one_text,
+            mesh: PLAYFIELD.scale_mesh(one_text),
         });
 
         let mut two_text = obj_lines_to_mesh(&include_str!("assets/two.obj"));
@@ -342,7 +1673,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: TWO_TEXT_HANDLE,
-            mesh: two_text,
+            mesh: PLAYFIELD.scale_mesh(two_text),
         });
 
         let mut three_text = obj_lines_to_mesh(&include_str!("assets/three.obj"));
@@ -354,7 +1685,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: THREE_TEXT_HANDLE,
-            mesh: three_text,
+            mesh: PLAYFIELD.scale_mesh(three_text),
         });
 
         let mut four_text = obj_lines_to_mesh(&include_str!("assets/four.obj"));
@@ -366,7 +1697,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: FOUR_TEXT_HANDLE,
-            mesh: four_text,
+            mesh: PLAYFIELD.scale_mesh(four_text),
         });
 
         let mut five_text = obj_lines_to_mesh(&include_str!("assets/five.obj"));
@@ -378,7 +1709,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: FIVE_TEXT_HANDLE,
-            mesh: five_text,
+            mesh: PLAYFIELD.scale_mesh(five_text),
         });
 
         let mut six_text = obj_lines_to_mesh(&include_str!("assets/six.obj"));
@@ -390,7 +1721,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: SIX_TEXT_HANDLE,
-            mesh: six_text,
+            mesh: PLAYFIELD.scale_mesh(six_text),
         });
 
         let mut seven_text = obj_lines_to_mesh(&include_str!("assets/seven.obj"));
@@ -402,7 +1733,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: SEVEN_TEXT_HANDLE,
-            mesh: seven_text,
+            mesh: PLAYFIELD.scale_mesh(seven_text),
         });
 
         let mut eight_text = obj_lines_to_mesh(&include_str!("assets/eight.obj"));
@@ -414,7 +1745,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: EIGHT_TEXT_HANDLE,
-            mesh: eight_text,
+            mesh: PLAYFIELD.scale_mesh(eight_text),
         });
 
         let mut nine_text = obj_lines_to_mesh(&include_str!("assets/nine.obj"));
@@ -426,7 +1757,7 @@ impl UserState for ClientState {
 
         io.send(&UploadMesh {
             id: NINE_TEXT_HANDLE,
-            mesh: nine_text,
+            mesh: PLAYFIELD.scale_mesh(nine_text),
         });
 
         // Add player movement input based on keyboard/controller input
@@ -434,7 +1765,6 @@ impl UserState for ClientState {
             .add_system(Self::player_input_movement_update)
             .subscribe::<InputEvent>()
             .subscribe::<GamepadState>()
-            .subscribe::<FrameTime>()
             .build();
 
         // Add player fire input based on keyboard/controller input
@@ -442,9 +1772,30 @@ impl UserState for ClientState {
             .add_system(Self::player_input_fire_update)
             .subscribe::<InputEvent>()
             .subscribe::<GamepadState>()
+            .subscribe::<FrameTime>()
+            .build();
+
+        // Add player weapon switch input based on keyboard/controller input
+        sched
+            .add_system(Self::player_input_weapon_update)
+            .subscribe::<InputEvent>()
+            .subscribe::<GamepadState>()
+            .build();
+
+        // Add glyph mesh upload for the text labels sent by the server
+        sched
+            .add_system(Self::text_label_mesh_update)
+            .query(
+                "Text_Label",
+                Query::new().intersect::<TextLabel>(Access::Read),
+            )
             .build();
 
-        Self::default()
+        Self {
+            keyboard_fire: Autofire::new(KEYBOARD_AUTOFIRE, AUTOFIRE_RATE),
+            gamepad_fire: Autofire::new(GAMEPAD_AUTOFIRE, AUTOFIRE_RATE),
+            ..Default::default()
+        }
     }
 }
 
@@ -455,9 +1806,6 @@ impl ClientState {
         // Declare the player movement direction as a vector: initially zero
         let mut direction = Vec3::ZERO;
 
-        // Read the frame time from the engine
-        let Some(frame_time) = io.inbox_first::<FrameTime>() else { return };
-
         // Read the input events from the keyboard
         self.input.handle_input_events(io);
 
@@ -491,11 +1839,8 @@ impl ClientState {
 
             // If there was an update in the direction vector (that is no longer zero), send the movement command to the server side
             if direction != Vec3::ZERO {
-                // Recalculate the direction vector based on the frame time and the player speed
-                let distance = direction.normalize() * frame_time.delta * PLAYER_SPEED;
-
-                // Create the Move command
-                let command = MoveCommand(distance);
+                // Create the Move command with the direction as one unit long (the server applies the player speed)
+                let command = MoveCommand(direction.normalize());
 
                 // Send the command to the server side
                 io.send(&command);
@@ -505,490 +1850,1409 @@ impl ClientState {
 
     // Send the player fire input to the server side
     fn player_input_fire_update(&mut self, io: &mut EngineIo, _query: &mut QueryResult) {
+        // Read the frame time from the engine
+        let Some(frame_time) = io.inbox_first::<FrameTime>() else { return };
+
         // Read the input events from the keyboard
         self.input.handle_input_events(io);
 
-        // Read the gamepad state from the engine
+        // Check if the East side button (fire) and the West side button (autofire toggle) on the right side of the controller are held
+        let mut gamepad_fire_held = false;
+        let mut gamepad_toggle_held = false;
         if let Some(GamepadState(gamepads)) = io.inbox_first() {
             // If gamepad input was received
             if let Some(gamepad) = gamepads.into_iter().next() {
-                // Check if the East side button on the right side of the controller was triggered
-                if gamepad.buttons[&Button::East] {
-                    // Create the Fire command
-                    let command = FireCommand(true);
-                    // Send the command to the server side
-                    io.send(&command);
-                }
+                gamepad_fire_held = gamepad.buttons[&Button::East];
+                gamepad_toggle_held = gamepad.buttons[&Button::West];
             }
         }
 
-        // If the keyboard input was received and the key Space was pressed, send the fire command to the server side
-        if self.input.key_pressed(KeyCode::Space) {
-            let command = FireCommand(true);
+        // If the West button was just pressed, toggle the gamepad autofire
+        if gamepad_toggle_held && !self.autofire_toggle_held {
+            self.gamepad_fire.enabled = !self.gamepad_fire.enabled;
+        }
+        self.autofire_toggle_held = gamepad_toggle_held;
+
+        // If the key F was pressed, toggle the keyboard autofire
+        if self.input.key_pressed(KeyCode::F) {
+            self.keyboard_fire.enabled = !self.keyboard_fire.enabled;
+        }
+
+        // Update the autofire timers of both devices
+        let keyboard_fire_held = self.input.key_held(KeyCode::Space);
+        let gamepad_shot = self
+            .gamepad_fire
+            .update(gamepad_fire_held, frame_time.delta);
+        let keyboard_shot = self
+            .keyboard_fire
+            .update(keyboard_fire_held, frame_time.delta);
+
+        // If fire is held on either device, send the fire command to the server side
+        if gamepad_fire_held || keyboard_fire_held {
+            // Tell the server whether a shot should be fired on this frame
+            let command = FireCommand(gamepad_shot || keyboard_shot);
             io.send(&command);
         }
     }
+
+    // Send the player weapon switch input to the server side
+    fn player_input_weapon_update(&mut self, io: &mut EngineIo, _query: &mut QueryResult) {
+        // Read the input events from the keyboard
+        self.input.handle_input_events(io);
+
+        // Check if the North side button on the right side of the controller is held
+        let mut gamepad_held = false;
+        if let Some(GamepadState(gamepads)) = io.inbox_first() {
+            if let Some(gamepad) = gamepads.into_iter().next() {
+                gamepad_held = gamepad.buttons[&Button::North];
+            }
+        }
+
+        // Only switch when the button was just pressed, not every frame it is held
+        let gamepad_pressed = gamepad_held && !self.switch_weapon_held;
+        self.switch_weapon_held = gamepad_held;
+
+        // If the key Q or the North button was pressed, send the switch weapon command to the server side
+        if gamepad_pressed || self.input.key_pressed(KeyCode::Q) {
+            io.send(&SwitchWeaponCommand);
+        }
+    }
+
+    // Send the glyph meshes that the text labels need and that were not sent yet
+    fn text_label_mesh_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        for entity in query.iter("Text_Label") {
+            let label = query.read::<TextLabel>(entity);
+            for character in label.characters() {
+                let key = (
+                    *character,
+                    label.size.to_bits(),
+                    label.color.map(f32::to_bits),
+                );
+                if self.uploaded_glyphs.insert(key) {
+                    io.send(&UploadMesh {
+                        id: glyph_handle(*character, label.size, label.color),
+                        mesh: PLAYFIELD.scale_mesh(glyph(*character, label.size, label.color)),
+                    });
+                }
+            }
+        }
+    }
 }
 
 // All state associated with server-side behaviour
-struct ServerState;
+struct ServerState {
+    // The pairs of entities that collided on this frame
+    collisions: Vec<(EntityId, EntityId)>,
+    // The hitboxes computed from the player and enemy obj files (the colliders and the weapon muzzles are placed from them)
+    player_hitbox: Hitbox,
+    enemy_hitbox: Hitbox,
+    // The frame time that has not been simulated by a fixed tick yet
+    accumulator: f32,
+    // Where the window sits in the world (the PLAYFIELD constant)
+    playfield: Playfield,
+    // How many ticks the gameplay steps on this frame, which tick slot the schedule is on,
+    // and whether the gameplay systems of the current slot step
+    ticks: u32,
+    slot: u32,
+    tick: bool,
+    // The player input received from the client for the ticks of this frame; it is kept over the frames without a tick,
+    // and cleared once a frame has stepped, so that every tick of a frame reads the same input
+    move_direction: Vec3,
+    fire_held: bool,
+    // Whether the client asked for a shot; only the first tick that reads it pulls the trigger for it
+    fire_shot: bool,
+    // The gameplay time in seconds, counted in fixed ticks
+    time: f32,
+    // Whether the stick/keys moved and fire were held on the last frame, so that only fresh presses count on the menus
+    menu_move_held: bool,
+    menu_fire_held: bool,
+    // The text label entities of the game over and attract screens, and the phase they were made for
+    screen_labels: Vec<EntityId>,
+    screen_phase: Option<GamePhase>,
+    // The points the enemies are worth
+    scoring: ScoringTable,
+    // The scores where a bonus ship is given
+    extra_life_table: ExtraLifeTable,
+}
 
 // Implement server only side functions that will update on the server side
 impl UserState for ServerState {
     // Implement a constructor
     fn new(io: &mut EngineIo, sched: &mut EngineSchedule<Self>) -> Self {
-        // Create a player status entity (not player entity)
-        io.create_entity()
-            // Add the player status component with the default values
-            .add_component(PlayerStatus::default())
-            // Build the entity
+        // Compute the player and enemy hitboxes from the obj files, rotated the same way as the entities
+        let player_hitbox = Hitbox::from_mesh(
+            &obj_lines_to_mesh(&include_str!("assets/galagaship.obj")),
+            Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.),
+            PLAYER_HITBOX_SCALE,
+        );
+        let enemy_hitbox = Hitbox::from_mesh(
+            &obj_lines_to_mesh(&include_str!("assets/galaga_enemy.obj")),
+            Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.),
+            ENEMY_HITBOX_SCALE,
+        );
+
+        // Place the window in the world
+        let playfield = PLAYFIELD;
+
+        // Attach Setup Function to the Engine schedule (before every other system); it creates the game entities only when they are missing,
+        // since the entities can outlive a plugin reload and creating them again would leave two of each
+        sched
+            // Add the setup system
+            .add_system(Self::setup_update)
+            // Add the query to the system
+            .query(
+                // The query name is "Game_State"
+                "Game_State",
+                // The query is fetch all the entities that have the GameState component with a permission to only read the component
+                Query::new().intersect::<GameState>(Access::Read),
+            )
+            // Add another query to the system
+            .query(
+                // The query name is "High_Score_Table"
+                "High_Score_Table",
+                // The query is fetch all the entities that have the HighScoreTable component with a permission to only read the component
+                Query::new().intersect::<HighScoreTable>(Access::Read),
+            )
+            // Build that system
             .build();
 
-        // Create an enemy status entity (not enemy entity)
-        io.create_entity().add_component(EnemyStatus(0.0)).build();
+        // Attach Fixed Timestep Function to the Engine schedule (before every gameplay system)
+        sched
+            // Add the fixed timestep system
+            .add_system(Self::fixed_timestep_update)
+            // Subscribe to the FrameTime event
+            .subscribe::<FrameTime>()
+            // Build that system
+            .build();
 
-        // Create a score entity
-        io.create_entity()
-            // Add the score component with the initial score of 0
-            .add_component(Score::default())
-            // Build the entity
-            .build();
-
-        // Create Player entity with components
-        io.create_entity()
-            // Add the transform component for movement
-            .add_component(
-                // Add the default transform component
-                Transform::default()
-                    // Set the bottom middle of the screen as the initial position
-                    .with_position(Vec3::new(0.0, -50.0, 0.0))
-                    // Set the initial rotation to be facing towards to the player based on the camera angle (no needed if you create the object facing a different direction)
-                    .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
+        // Attach Game Input Function to the Engine schedule (this is not tick gated, so no press is missed)
+        sched
+            // Add the game input system
+            .add_system(Self::game_input_update)
+            // Subscribe to the MoveCommand event/message
+            .subscribe::<MoveCommand>()
+            // Subscribe to the FireCommand event/message
+            .subscribe::<FireCommand>()
+            // Add the query to the system
+            .query(
+                // The query name is "Game_State"
+                "Game_State",
+                // The query is fetch all the entities that have the GameState component with a permission to write the component
+                Query::new().intersect::<GameState>(Access::Write),
             )
-            // Add the render component to draw the player with lines
-            .add_component(Render::new(PLAYER_HANDLE).primitive(Primitive::Lines))
-            // Add the player component as default
-            .add_component(Player::default())
-            // Add the synchronized component to synchronize the entity with the client side
-            .add_component(Synchronized)
-            // Build the entity
-            .build();
-
-        // Create Enemy with components
-        io.create_entity()
-            // Add the transform component for movement, firing, and displaying
-            .add_component(
-                // Add the default transform component
-                Transform::default()
-                    // Set the top middle of the screen as the initial position
-                    .with_position(Vec3::new(0.0, 50.0, 0.0))
-                    // Set the initial rotation to be facing towards to the player based on the camera angle
-                    // (no needed if you create the object facing a different direction or differen angle rotation)
-                    .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
+            // Add another query to the system
+            .query(
+                // The query name is "High_Score_Table"
+                "High_Score_Table",
+                // The query is fetch all the entities that have the HighScoreTable component with a permission to write the component
+                Query::new().intersect::<HighScoreTable>(Access::Write),
             )
-            // Add the render component to draw the enemy with lines
-            .add_component(Render::new(ENEMY_HANDLE).primitive(Primitive::Lines))
-            // Add the synchronized component to synchronize the entity with the client side
-            .add_component(Synchronized)
-            // Add the enemy component as default
-            .add_component(Enemy::default())
-            // Build the entity
+            // Add another query to the system
+            .query(
+                // The query name is "Score_Update"
+                "Score_Update",
+                // The query is fetch all the entities that have the Score component with a permission to write the component
+                Query::new().intersect::<Score>(Access::Write),
+            )
+            // Build that system
             .build();
 
-        // Create the Window entity with components
-        io.create_entity()
-            // Add the transform component for displaying the window
-            .add_component(Transform::default())
-            // Add the render component to draw the window with lines
-            .add_component(Render::new(WINDOW_SIZE_HANDLE).primitive(Primitive::Lines))
-            // Add the synchronized component to synchronize the entity with the client side
-            .add_component(Synchronized)
-            // Build the entity
+        // Attach Player Fire Function to the Engine schedule (this reads the fire input once a frame for every tick)
+        sched
+            // Add the player fire system
+            .add_system(Self::player_fire_update)
+            // Subscribe to the FireCommand event/message
+            .subscribe::<FireCommand>()
+            // Build that system
             .build();
 
-        // Attach Spawn Player Function to the Engine schedule
+        // Attach Player Move Command Function to the Engine schedule (this reads the movement input once a frame for every tick)
         sched
-            // Add the spawn player system
-            .add_system(Self::spawn_player)
-            // Subscribe to the FrameTime event
-            .subscribe::<FrameTime>()
-            // Add the query to the system
-            .query(
-                // The query name is "Player"
-                "Player",
-                // The query is fetch all the entities that have the PlayerStatus component with a permission to modify the component
-                Query::new().intersect::<PlayerStatus>(Access::Write),
-            )
+            // Add the player move command system
+            .add_system(Self::player_move_command_update)
+            // Subscribe to the MoveCommand event/message
+            .subscribe::<MoveCommand>()
             // Build that system
             .build();
 
-        // Attach Spawn Enemy Function to the Engine schedule
+        // Attach Player Weapon Switch Function to the Engine schedule
         sched
-            // Add the spawn enemy system
-            .add_system(Self::spawn_enemy)
-            // Subscribe to the FrameTime event
-            .subscribe::<FrameTime>()
+            // Add the player weapon switch system
+            .add_system(Self::player_weapon_switch_update)
+            // Subscribe to the SwitchWeaponCommand event/message
+            .subscribe::<SwitchWeaponCommand>()
             // Add the query to the system
             .query(
-                // The query name is "Enemy_Count"
-                "Enemy_Count",
-                // The query is fetch all the entities that have the Enemy component with a permission to modify the component
-                Query::new().intersect::<Enemy>(Access::Write),
-            )
-            // Add another query to the system
-            .query(
-                // The query name is "Enemy_Status"
-                "Enemy_Status",
-                // The query is fetch all the entities that have the EnemyStatus component with a permission to modify the component
-                Query::new().intersect::<EnemyStatus>(Access::Write),
+                // The query name is "Player_Weapon"
+                "Player_Weapon",
+                // The query is fetch all the entities that have the Player and Weapon component
+                // The Player will only have the permission to read whereas the Weapon will have the permission to write
+                Query::new()
+                    .intersect::<Player>(Access::Read)
+                    .intersect::<Weapon>(Access::Write),
             )
             // Build that system
             .build();
 
-        // Attach Player Movement Function to the Engine schedule
+        // Attach the gameplay systems to the Engine schedule once for every tick a frame can catch up on;
+        // each copy starts with the tick slot system, which decides whether that copy steps the gameplay.
+        // One system looping over the ticks does not work: the engine only creates and removes the entities asked for
+        // through the EngineIo after the system returns, and the query only holds what existed when the system started,
+        // so the bullets fired on one tick could not move or collide on the next one, and the destroyed enemies would keep being hit.
+        // Every copy still runs its queries on the frames without a tick; the input messages are read once before the copies,
+        // so that no copy reads the same commands again
+        for _ in 0..MAX_TICKS_PER_FRAME {
+            // Attach Tick Slot Function to the Engine schedule
+            sched
+                // Add the tick slot system
+                .add_system(Self::tick_slot_update)
+                // Build that system
+                .build();
+
+            // Attach Spawn Player Function to the Engine schedule
+            sched
+                // Add the spawn player system
+                .add_system(Self::spawn_player)
+                // Add the query to the system
+                .query(
+                    // The query name is "Player"
+                    "Player",
+                    // The query is fetch all the entities that have the PlayerStatus component with a permission to modify the component
+                    Query::new().intersect::<PlayerStatus>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Game_State"
+                    "Game_State",
+                    // The query is fetch all the entities that have the GameState component with a permission to only read the component
+                    Query::new().intersect::<GameState>(Access::Read),
+                )
+                // Build that system
+                .build();
+
+            // Attach Spawn Enemy Function to the Engine schedule
+            sched
+                // Add the spawn enemy system
+                .add_system(Self::spawn_enemy)
+                // Add the query to the system
+                .query(
+                    // The query name is "Enemy_Count"
+                    "Enemy_Count",
+                    // The query is fetch all the entities that have the Enemy component with a permission to modify the component
+                    Query::new().intersect::<Enemy>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Enemy_Status"
+                    "Enemy_Status",
+                    // The query is fetch all the entities that have the EnemyStatus component with a permission to modify the component
+                    Query::new().intersect::<EnemyStatus>(Access::Write),
+                )
+                // Build that system
+                .build();
+
+            // Attach Player Movement Function to the Engine schedule
+            sched
+                // Add the player movement system
+                .add_system(Self::player_movement_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Player_Movement"
+                    "Player_Movement",
+                    // The query is fetch all the entities that have the LocalTransform, Player, Collider and Velocity component
+                    // The Velocity will have the permission to modify whereas the others will only have the permission to read
+                    Query::new()
+                        .intersect::<LocalTransform>(Access::Read)
+                        .intersect::<Player>(Access::Read)
+                        .intersect::<Collider>(Access::Read)
+                        .intersect::<Velocity>(Access::Write),
+                )
+                // Build that system
+                .build();
+
+            // Attach Enemy Movement Function to the Engine schedule
+            sched
+                // Add the enemy movement system
+                .add_system(Self::enemy_movement_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Enemy_Movement"
+                    "Enemy_Movement",
+                    // The query is fetch all the entities that have the LocalTransform, Enemy, Collider and Velocity component
+                    // The LocalTransform, Enemy and Velocity will have the permission to modify whereas the Collider will only have the permission to read
+                    Query::new()
+                        .intersect::<LocalTransform>(Access::Write)
+                        .intersect::<Enemy>(Access::Write)
+                        .intersect::<Collider>(Access::Read)
+                        .intersect::<Velocity>(Access::Write),
+                )
+                // Build that system
+                .build();
+
+            // Attach Enemy Fire Function to the Engine schedule
+            sched
+                // Add the enemy fire system
+                .add_system(Self::enemy_fire_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Enemy_Fire_Input"
+                    "Enemy_Fire_Input",
+                    // The query is fetch all the entities that have the Enemy and Weapon component
+                    // The Enemy will only have the permission to read whereas the Weapon will have the permission to write
+                    Query::new()
+                        .intersect::<Enemy>(Access::Read)
+                        .intersect::<Weapon>(Access::Write),
+                )
+                // Build that system
+                .build();
+
+            // Attach Player Trigger Function to the Engine schedule
+            sched
+                // Add the player trigger system
+                .add_system(Self::player_trigger_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Player_Fire_Input"
+                    "Player_Fire_Input",
+                    // The query is fetch all the entities that have the Player and Weapon component
+                    // The Player will only have the permission to read whereas the Weapon will have the permission to write
+                    Query::new()
+                        .intersect::<Player>(Access::Read)
+                        .intersect::<Weapon>(Access::Write),
+                )
+                // Build that system
+                .build();
+
+            // Attach Laser Beam Function to the Engine schedule (before the Weapon Fire Function releases the trigger)
+            sched
+                // Add the laser beam system
+                .add_system(Self::laser_beam_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Laser"
+                    "Laser",
+                    // The query is fetch all the entities that have the Player, LocalTransform and Weapon component
+                    // The Player and LocalTransform will only have the permission to read whereas the Weapon will have the permission to write
+                    Query::new()
+                        .intersect::<Player>(Access::Read)
+                        .intersect::<LocalTransform>(Access::Read)
+                        .intersect::<Weapon>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Laser_Beam"
+                    "Laser_Beam",
+                    // The query is fetch all the entities that have the LaserBeam, LocalTransform and Render component with a permission to modify the component
+                    Query::new()
+                        .intersect::<LaserBeam>(Access::Write)
+                        .intersect::<LocalTransform>(Access::Write)
+                        .intersect::<Render>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Energy_Meter"
+                    "Energy_Meter",
+                    // The query is fetch all the entities that have the EnergyMeter and Render component with a permission to modify the component
+                    Query::new()
+                        .intersect::<EnergyMeter>(Access::Write)
+                        .intersect::<Render>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Laser_Target"
+                    "Laser_Target",
+                    // The query is fetch all the entities that have the Enemy, LocalTransform and Collider component
+                    // The Enemy will have the permission to write whereas the LocalTransform and Collider will only have the permission to read
+                    Query::new()
+                        .intersect::<Enemy>(Access::Write)
+                        .intersect::<LocalTransform>(Access::Read)
+                        .intersect::<Collider>(Access::Read),
+                )
+                // Build that system
+                .build();
+
+            // Attach Weapon Fire Function to the Engine schedule
+            sched
+                // Add the weapon fire system
+                .add_system(Self::weapon_fire_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Weapon"
+                    "Weapon",
+                    // The query is fetch all the entities that have the LocalTransform and Weapon component
+                    // The LocalTransform will only have the permission to read whereas the Weapon will have the permission to write
+                    Query::new()
+                        .intersect::<LocalTransform>(Access::Read)
+                        .intersect::<Weapon>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Game_State"
+                    "Game_State",
+                    // The query is fetch all the entities that have the GameState component with a permission to write the component
+                    Query::new().intersect::<GameState>(Access::Write),
+                )
+                // Build that system
+                .build();
+
+            // Attach Missile Homing Function to the Engine schedule
+            sched
+                // Add the missile homing system
+                .add_system(Self::missile_homing_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Missile"
+                    "Missile",
+                    // The query is fetch all the entities that have the LocalTransform, Bullet, Missile and Velocity component with a permission to modify the component
+                    Query::new()
+                        .intersect::<LocalTransform>(Access::Write)
+                        .intersect::<Bullet>(Access::Write)
+                        .intersect::<Missile>(Access::Write)
+                        .intersect::<Velocity>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Missile_Target"
+                    "Missile_Target",
+                    // The query is fetch all the entities that have the Enemy and LocalTransform component with a permission to only read the component
+                    Query::new()
+                        .intersect::<Enemy>(Access::Read)
+                        .intersect::<LocalTransform>(Access::Read),
+                )
+                // Build that system
+                .build();
+
+            // Attach Velocity Integration Function to the Engine schedule (after every system that changes the velocity)
+            sched
+                // Add the velocity integration system
+                .add_system(Self::velocity_integration_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Velocity"
+                    "Velocity",
+                    // The query is fetch all the entities that have the LocalTransform and Velocity component
+                    // The LocalTransform will have the permission to modify whereas the Velocity will only have the permission to read
+                    Query::new()
+                        .intersect::<LocalTransform>(Access::Write)
+                        .intersect::<Velocity>(Access::Read),
+                )
+                // Build that system
+                .build();
+
+            // Attach Cleanup Function to the Engine schedule (after the movement, so that nothing gone collides)
+            sched
+                // Add the cleanup system
+                .add_system(Self::cleanup_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Lifetime"
+                    "Lifetime",
+                    // The query is fetch all the entities that have the Lifetime component with a permission to write the component
+                    Query::new().intersect::<Lifetime>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Despawn_Outside_Playfield"
+                    "Despawn_Outside_Playfield",
+                    // The query is fetch all the entities that have the LocalTransform and DespawnOutsidePlayfield component with a permission to only read the component
+                    Query::new()
+                        .intersect::<LocalTransform>(Access::Read)
+                        .intersect::<DespawnOutsidePlayfield>(Access::Read),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Bullet_Owner"
+                    "Bullet_Owner",
+                    // The query is fetch all the entities that have the Bullet component with a permission to only read the component
+                    Query::new().intersect::<Bullet>(Access::Read),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Bullet_Count_Update"
+                    "Bullet_Count_Update",
+                    // The query is fetch all the entities that have the Weapon component with a permission to write the component
+                    Query::new().intersect::<Weapon>(Access::Write),
+                )
+                // Build that system
+                .build();
+
+            // Attach Collision Function to the Engine schedule
+            sched
+                // Add the collision system
+                .add_system(Self::collision_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Collider"
+                    "Collider",
+                    // The query is fetch all the entities that have the LocalTransform and Collider component with a permission to only read the component
+                    Query::new()
+                        .intersect::<LocalTransform>(Access::Read)
+                        .intersect::<Collider>(Access::Read),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Moving"
+                    "Moving",
+                    // The query is fetch all the entities that have the Velocity component with a permission to only read the component
+                    Query::new().intersect::<Velocity>(Access::Read),
+                )
+                // Build that system
+                .build();
+
+            // Attach Collision Response Function to the Engine schedule
+            sched
+                // Add the collision response system
+                .add_system(Self::collision_response_update)
+                // Add the query to the system
+                .query(
+                    // The query name is "Collider"
+                    "Collider",
+                    // The query is fetch all the entities that have the Collider and LocalTransform component with a permission to only read the components
+                    Query::new()
+                        .intersect::<Collider>(Access::Read)
+                        .intersect::<LocalTransform>(Access::Read),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Bullet"
+                    "Bullet",
+                    // The query is fetch all the entities that have the Bullet component with a permission to write the component
+                    Query::new().intersect::<Bullet>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Enemy"
+                    "Enemy",
+                    // The query is fetch all the entities that have the Enemy component with a permission to write the component
+                    Query::new().intersect::<Enemy>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Player_Status_Update"
+                    "Player_Status_Update",
+                    // The query is fetch all the entities that have the PlayerStatus component with a permission to write the component
+                    Query::new().intersect::<PlayerStatus>(Access::Write),
+                )
+                // Add another query to the system
+                .query(
+                    // The query name is "Bullet_Count_Update"
+                    "Bullet_Count_Update",
+                    // The query is fetch all the entities that have the Weapon component with a permission to write the component
+                    Query::new().intersect::<Weapon>(Access::Write),
+                )
+                // Build that system
+                .build();
+        }
+
+        // Attach Score Function to the Engine schedule
         sched
-            // Add the player movement system
-            .add_system(Self::player_movement_update)
-            // Subscribe to the MoveCommand event/message
-            .subscribe::<MoveCommand>()
+            // Add the score system
+            .add_system(Self::score_update)
+            // Subscribe to the HitEvent event/message
+            .subscribe::<HitEvent>()
             // Add the query to the system
             .query(
-                // The query name is "Player_Movement"
-                "Player_Movement",
-                // The query is fetch all the entities that have the Transform and Player component with a permission to modify the component
-                Query::new()
-                    .intersect::<Transform>(Access::Write)
-                    .intersect::<Player>(Access::Write),
+                // The query name is "Score_Update"
+                "Score_Update",
+                // The query is fetch all the entities that have the Score component with a permission to write the component
+                Query::new().intersect::<Score>(Access::Write),
             )
             // Build that system
             .build();
 
-        // Attach Enemy Movement Function to the Engine schedule
+        // Attach Score Popup Function to the Engine schedule (after the score is updated from the same hits)
         sched
-            // Add the enemy movement system
-            .add_system(Self::enemy_movement_update)
-            // Subscribe to the FrameTime event
-            .subscribe::<FrameTime>()
+            // Add the score popup system
+            .add_system(Self::score_popup_update)
+            // Subscribe to the ScoreEvent event/message
+            .subscribe::<ScoreEvent>()
             // Add the query to the system
             .query(
-                // The query name is "Enemy_Movement"
-                "Enemy_Movement",
-                // The query is fetch all the entities that have the Transform and Enemy component with a permission to modify the component
+                // The query name is "Score_Popup"
+                "Score_Popup",
+                // The query is fetch all the entities that have the ScorePopup, Lifetime and TextLabel component
+                // The ScorePopup and Lifetime will only have the permission to read whereas the TextLabel will have the permission to write
                 Query::new()
-                    .intersect::<Transform>(Access::Write)
-                    .intersect::<Enemy>(Access::Write),
+                    .intersect::<ScorePopup>(Access::Read)
+                    .intersect::<Lifetime>(Access::Read)
+                    .intersect::<TextLabel>(Access::Write),
             )
             // Build that system
             .build();
 
-        // Attach Player Fire Function to the Engine schedule
+        // Attach Combo Function to the Engine schedule
         sched
-            // Add the player fire system
-            .add_system(Self::player_fire_update)
-            // Subscribe to the FireCommand event/message
-            .subscribe::<FireCommand>()
+            // Add the combo system
+            .add_system(Self::combo_update)
             // Add the query to the system
             .query(
-                // The query name is "Player_Fire_Input"
-                "Player_Fire_Input",
-                // The query is fetch all the entities that have the Player component with a permission to only read the component
-                Query::new().intersect::<Player>(Access::Read),
+                // The query name is "Score_Update"
+                "Score_Update",
+                // The query is fetch all the entities that have the Score component with a permission to write the component
+                Query::new().intersect::<Score>(Access::Write),
+            )
+            // Add another query to the system
+            .query(
+                // The query name is "Combo_Label"
+                "Combo_Label",
+                // The query is fetch all the entities that have the ComboLabel and TextLabel component
+                // The ComboLabel will only have the permission to read whereas the TextLabel will have the permission to write
+                Query::new()
+                    .intersect::<ComboLabel>(Access::Read)
+                    .intersect::<TextLabel>(Access::Write),
             )
             // Build that system
             .build();
 
-        // Attach Player Bullet Movement Function to the Engine schedule
+        // Attach Game State Function to the Engine schedule (after the score is updated from the same hits)
         sched
-            // Add the player bullet movement system
-            .add_system(Self::player_bullet_movement_update)
-            // Subscribe to the FrameTime event
-            .subscribe::<FrameTime>()
+            // Add the game state system
+            .add_system(Self::game_state_update)
+            // Subscribe to the HitEvent event/message
+            .subscribe::<HitEvent>()
             // Add the query to the system
             .query(
-                // The query name is "Player_Bullet_Movement"
-                "Player_Bullet_Movement",
-                // The query is fetch all the entities that have the Transform and Bullet component with a permission to modify the component
-                Query::new()
-                    .intersect::<Transform>(Access::Write)
-                    .intersect::<Bullet>(Access::Write),
+                // The query name is "Game_State"
+                "Game_State",
+                // The query is fetch all the entities that have the GameState component with a permission to write the component
+                Query::new().intersect::<GameState>(Access::Write),
+            )
+            // Add another query to the system
+            .query(
+                // The query name is "Score"
+                "Score",
+                // The query is fetch all the entities that have the Score component with a permission to only read the component
+                Query::new().intersect::<Score>(Access::Read),
             )
             // Build that system
             .build();
 
-        // Attach Enemy Fire Function to the Engine schedule
+        // Attach Game Screen Function to the Engine schedule
         sched
-            // Add the enemy fire system
-            .add_system(Self::enemy_fire_update)
+            // Add the game screen system
+            .add_system(Self::game_screen_update)
             // Add the query to the system
             .query(
-                // The query name is "Enemy_Fire_Input"
-                "Enemy_Fire_Input",
-                // The query is fetch all the entities that have the Enemy component with a permission to write the component
-                Query::new().intersect::<Enemy>(Access::Write),
+                // The query name is "Game_State"
+                "Game_State",
+                // The query is fetch all the entities that have the GameState component with a permission to only read the component
+                Query::new().intersect::<GameState>(Access::Read),
+            )
+            // Add another query to the system
+            .query(
+                // The query name is "High_Score_Table"
+                "High_Score_Table",
+                // The query is fetch all the entities that have the HighScoreTable component with a permission to only read the component
+                Query::new().intersect::<HighScoreTable>(Access::Read),
+            )
+            // Add another query to the system
+            .query(
+                // The query name is "Screen_Label"
+                "Screen_Label",
+                // The query is fetch all the entities that have the TextLabel component with a permission to write the component
+                Query::new().intersect::<TextLabel>(Access::Write),
             )
             // Build that system
             .build();
 
-        // Attach Enemy Bullet Movement Function to the Engine schedule
+        // Attach Lives Function to the Engine schedule
         sched
-            // Add the enemy bullet movement system
-            .add_system(Self::enemy_bullet_movement_update)
-            // Subscribe to the FrameTime event
-            .subscribe::<FrameTime>()
+            // Add the lives system
+            .add_system(Self::lives_update)
             // Add the query to the system
             .query(
-                // The query name is "Enemy_Bullet_Movement"
-                "Enemy_Bullet_Movement",
-                // The query is fetch all the entities that have the Transform and Bullet component with a permission to modify the component
+                // The query name is "Game_State"
+                "Game_State",
+                // The query is fetch all the entities that have the GameState component with a permission to write the component
+                Query::new().intersect::<GameState>(Access::Write),
+            )
+            // Add another query to the system
+            .query(
+                // The query name is "Hud_Icon"
+                "Hud_Icon",
+                // The query is fetch all the entities that have the HudIcon and HudAnchor component with a permission to only read the components
                 Query::new()
-                    .intersect::<Transform>(Access::Write)
-                    .intersect::<Bullet>(Access::Write),
+                    .intersect::<HudIcon>(Access::Read)
+                    .intersect::<HudAnchor>(Access::Read),
             )
             // Add another query to the system
             .query(
-                // The query name is "Enemy_Bullet_Count_Update"
-                "Enemy_Bullet_Count_Update",
-                // The query is fetch all the entities that have the Enemy component with a permission to write the component
-                Query::new().intersect::<Enemy>(Access::Write),
+                // The query name is "Extra_Life_Label"
+                "Extra_Life_Label",
+                // The query is fetch all the entities that have the ExtraLifeLabel and TextLabel component
+                // The ExtraLifeLabel will only have the permission to read whereas the TextLabel will have the permission to write
+                Query::new()
+                    .intersect::<ExtraLifeLabel>(Access::Read)
+                    .intersect::<TextLabel>(Access::Write),
             )
             // Build that system
             .build();
 
-        // Attach Player Bullet to Enemy Collision Function to the Engine schedule
+        // Attach Score Display Function to the Engine schedule
         sched
-            // Add the player bullet to enemy collision system
-            .add_system(Self::player_bullet_to_enemy_collision)
+            // Add the score display system
+            .add_system(Self::score_display)
             // Add the query to the system
             .query(
-                // The query name is "Player_Bullet"
-                "Player_Bullet",
-                // The query is fetch all the entities that have the Transform and Bullet component
-                // The Transform will only have the permission to read whereas the Bullet will have the permission to write
+                // The query name is "Score"
+                "Score",
+                // The query is fetch all the entities that have the Score and NumberDisplay component
+                // The Score will only have the permission to read whereas the NumberDisplay will have the permission to write
                 Query::new()
-                    .intersect::<Transform>(Access::Read)
-                    .intersect::<Bullet>(Access::Write),
+                    .intersect::<Score>(Access::Read)
+                    .intersect::<NumberDisplay>(Access::Write),
             )
             // Add another query to the system
             .query(
-                // The query name is "Enemy"
-                "Enemy",
-                // The query is fetch all the entities that have the Enemy and Transfrom component
-                // The Enemy will have the permission to write whereas the Transform will only have the permission to read
+                // The query name is "High_Score_Display"
+                "High_Score_Display",
+                // The query is fetch all the entities that have the HighScoreDisplay and NumberDisplay component
+                // The HighScoreDisplay will only have the permission to read whereas the NumberDisplay will have the permission to write
                 Query::new()
-                    .intersect::<Enemy>(Access::Write)
-                    .intersect::<Transform>(Access::Read),
+                    .intersect::<HighScoreDisplay>(Access::Read)
+                    .intersect::<NumberDisplay>(Access::Write),
             )
             // Add another query to the system
             .query(
-                // The query name is "Score_Update"
-                "Score_Update",
-                // The query is fetch all the entities that have the Score component with a permission to write the component
-                Query::new().intersect::<Score>(Access::Write),
+                // The query name is "High_Score_Table"
+                "High_Score_Table",
+                // The query is fetch all the entities that have the HighScoreTable component with a permission to only read the component
+                Query::new().intersect::<HighScoreTable>(Access::Read),
             )
             // Build that system
             .build();
 
-        // Attach Enemy Bullet to Player Collision Function to the Engine schedule
+        // Attach Stage Flag Function to the Engine schedule
         sched
-            // Add the enemy bullet to player collision system
-            .add_system(Self::enemy_bullet_to_player_collision)
+            // Add the stage flag system
+            .add_system(Self::stage_flag_update)
             // Add the query to the system
             .query(
-                // The query name is "Enemy_Bullet"
-                "Enemy_Bullet",
-                // The query is fetch all the entities that have the Transform and Bullet component
-                // The Transform will only have the permission to read whereas the Bullet will have the permission to write
-                Query::new()
-                    .intersect::<Transform>(Access::Read)
-                    .intersect::<Bullet>(Access::Write),
+                // The query name is "Game_State"
+                "Game_State",
+                // The query is fetch all the entities that have the GameState component with a permission to only read the component
+                Query::new().intersect::<GameState>(Access::Read),
             )
             // Add another query to the system
             .query(
-                // The query name is "Player"
-                "Player",
-                // The query is fetch all the entities that have the Player and Transfrom component
-                // The Player will have the permission to write whereas the Transform will only have the permission to read
+                // The query name is "Hud_Icon"
+                "Hud_Icon",
+                // The query is fetch all the entities that have the HudIcon and HudAnchor component with a permission to only read the components
                 Query::new()
-                    .intersect::<Player>(Access::Write)
-                    .intersect::<Transform>(Access::Read),
+                    .intersect::<HudIcon>(Access::Read)
+                    .intersect::<HudAnchor>(Access::Read),
             )
-            // Add another query to the system
+            // Build that system
+            .build();
+
+        // Attach HUD Text Function to the Engine schedule (after every system that changes a displayed number or text)
+        sched
+            // Add the HUD text system
+            .add_system(Self::hud_text_update)
+            // Add the query to the system
             .query(
-                // The query name is "Player_Status_Update"
-                "Player_Status_Update",
-                // The query is fetch all the entities that have the PlayerStatus component with a permission to write the component
-                Query::new().intersect::<PlayerStatus>(Access::Write),
+                // The query name is "Number_Display"
+                "Number_Display",
+                // The query is fetch all the entities that have the NumberDisplay component with a permission to write the component
+                Query::new().intersect::<NumberDisplay>(Access::Write),
             )
             // Add another query to the system
             .query(
-                // The query name is "Score_Update"
-                "Score_Update",
-                // The query is fetch all the entities that have the Score component with a permission to write the component
-                Query::new().intersect::<Score>(Access::Write),
+                // The query name is "Text_Label"
+                "Text_Label",
+                // The query is fetch all the entities that have the TextLabel and LocalTransform component
+                // The TextLabel will have the permission to write whereas the LocalTransform will only have the permission to read
+                Query::new()
+                    .intersect::<TextLabel>(Access::Write)
+                    .intersect::<LocalTransform>(Access::Read),
             )
             // Add another query to the system
             .query(
-                // The query name is "Enemy_Bullet_Count_Update"
-                "Enemy_Bullet_Count_Update",
-                // The query is fetch all the entities that have the Enemy component with a permission to write the component
-                Query::new().intersect::<Enemy>(Access::Write),
+                // The query name is "Glyph"
+                "Glyph",
+                // The query is fetch all the entities that have the Glyph, Render and LocalTransform component
+                // The Glyph will only have the permission to read whereas the Render and LocalTransform will have the permission to write
+                Query::new()
+                    .intersect::<Glyph>(Access::Read)
+                    .intersect::<Render>(Access::Write)
+                    .intersect::<LocalTransform>(Access::Write),
             )
             // Build that system
             .build();
 
+        // Attach Playfield Function to the Engine schedule (after every system that moves or creates entities)
         sched
-            .add_system(Self::score_display)
-            .query("Score", Query::new().intersect::<Score>(Access::Read))
+            // Add the playfield system
+            .add_system(Self::playfield_update)
+            // Add the query to the system
+            .query(
+                // The query name is "Playfield"
+                "Playfield",
+                // The query is fetch all the entities that have the LocalTransform and Transform component
+                // The LocalTransform will only have the permission to read whereas the Transform will have the permission to write
+                Query::new()
+                    .intersect::<LocalTransform>(Access::Read)
+                    .intersect::<Transform>(Access::Write),
+            )
+            // Build that system
             .build();
 
-        Self
+        Self {
+            collisions: Vec::new(),
+            player_hitbox,
+            enemy_hitbox,
+            playfield,
+            accumulator: 0.0,
+            ticks: 0,
+            slot: 0,
+            tick: false,
+            move_direction: Vec3::ZERO,
+            fire_held: false,
+            fire_shot: false,
+            time: 0.0,
+            menu_move_held: false,
+            menu_fire_held: false,
+            screen_labels: Vec::new(),
+            screen_phase: None,
+            scoring: ScoringTable::default(),
+            extra_life_table: ExtraLifeTable::default(),
+        }
     }
 }
 
 // Implement the function systems for the server
 impl ServerState {
-    // The function that will spawn the player
-    fn spawn_player(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // Get the FrameTime event
-        let Some(frame_time) = io.inbox_first::<FrameTime>() else { return };
-        // For every entity that qualify from the query "Player" will be processed
-        for entity in query.iter("Player") {
-            // If the player is dead
-            if !(query.read::<PlayerStatus>(entity).status) {
-                // Read the time when the PlayerStatus componenet
-                let mut dead_time = query.read::<PlayerStatus>(entity).dead_time;
-                // If the player just died
-                if dead_time == 0.0 {
-                    // Record the dead time to the current time
-                    dead_time = frame_time.time;
-                }
-                // If the player has been dead for a certain amount of time (PLAYER_SPAWN_TIME)
-                if dead_time + PLAYER_SPAWN_TIME < frame_time.time {
-                    // Recreate the player entity
-                    io.create_entity()
-                        .add_component(
-                            Transform::default()
-                                .with_position(Vec3::new(0.0, -50.0, 0.0))
-                                .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
-                        )
-                        .add_component(Render::new(PLAYER_HANDLE).primitive(Primitive::Lines))
-                        .add_component(Player::default())
-                        .add_component(Synchronized)
-                        .build();
-                    // Throw away the timer entity (PlayerStatus)
-                    io.remove_entity(entity);
-                    // Recreate the PlayerStatus entity with the default value
-                    io.create_entity()
-                        .add_component(PlayerStatus::default())
-                        .build();
-                }
-                // Otherwise, update the dead time on the PlayerStatus entity
-                else {
-                    query.modify::<PlayerStatus>(entity, |value| {
-                        value.dead_time = dead_time;
-                    })
-                }
-            }
+    // The function that will create the game entities the first time (after a plugin reload they are still there)
+    fn setup_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Create the high score table on its own, so that it is never replaced once it has entries
+        if query.iter("High_Score_Table").next().is_none() {
+            io.create_entity()
+                .add_component(HighScoreTable::default())
+                .build();
         }
-    }
-    // The function that will spawn the enemy
-    fn spawn_enemy(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // Get the FrameTime event
-        let Some(frame_time) = io.inbox_first::<FrameTime>() else { return };
-
-        // If there are less enemy entities on the screen than the max enemy count from the query "Enemy_Count"
-        if (query.iter("Enemy_Count").count() as u32) < ENEMY_COUNT {
-            // For every entity that qualify from the query "Enemy_Status" will be processed
-            for entity in query.iter("Enemy_Status") {
-                // Read the dead time from the EnemyStatus component
-                let mut dead_time = query.read::<EnemyStatus>(entity).0;
 
-                // If the enemy just died
-                if dead_time == 0.0 {
-                    // Record the dead time of the enemy to the current time
-                    dead_time = frame_time.time;
-                }
-
-                // If the enemy has been dead for a certain amount of time (ENEMY_SPAWN_TIME)
-                if dead_time + ENEMY_SPAWN_TIME < frame_time.time {
-                    // Recreate the enemy entity
-                    io.create_entity()
-                        .add_component(
-                            Transform::default()
-                                .with_position(Vec3::new(0.0, 50.0, 0.0))
-                                .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
-                        )
-                        .add_component(Render::new(ENEMY_HANDLE).primitive(Primitive::Lines))
-                        .add_component(Synchronized)
-                        .add_component(Enemy::default())
-                        .build();
-                    // Throw away the timer entity (EnemyStatus)
-                    io.remove_entity(entity);
-                    // Recreate the EnemyStatus entity with the default value
-                    io.create_entity().add_component(EnemyStatus(0.0)).build();
-                }
-                // Otherwise, update the dead time on the EnemyStatus entity
-                else {
-                    query.modify::<EnemyStatus>(entity, |value| {
-                        value.0 = dead_time;
-                    })
-                }
-            }
+        // The game state, the HUD, the player and the enemy are all created together; if the game state is there, so is the rest
+        if query.iter("Game_State").next().is_some() {
+            return;
         }
-    }
-    // The function that will handle the player movement
-    fn player_movement_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // When a MoveCommand event is received from the client
-        for player_movement in io.inbox::<MoveCommand>() {
-            // For every entity that qualify from the query "Player_Movement" will be processed
-            for entity in query.iter("Player_Movement") {
-                // Set the limit of the player movement
-                let x_limit = WITDH / 2.0;
-                // If the player is about to go out of bound
-                if query.read::<Player>(entity).current_position.x + player_movement.0.x
-                    - PLAYER_SIZE
-                    < -x_limit
-                    || query.read::<Player>(entity).current_position.x
-                        + player_movement.0.x
-                        + PLAYER_SIZE
-                        > x_limit
-                {
-                    // Do not move the player and conclude the function
-                    return;
-                }
 
-                // Otherwise, move the player
-                query.modify::<Transform>(entity, |transform| {
-                    transform.pos += player_movement.0;
-                });
-                // Update the new player position
-                query.modify::<Player>(entity, |player| {
-                    player.current_position += player_movement.0;
-                });
-            }
-        }
-    }
+        // Place the entities inside the window
+        let playfield = self.playfield;
 
-    // The function that will handle the enemy movement
-    fn enemy_movement_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // For every entity that qualify from the query "Enemy_Movement" will be processed
-        for entity in query.iter("Enemy_Movement") {
-            // Get the FrameTime event
-            let Some(frame_time) = io.inbox_first::<FrameTime>() else { return };
-            // Set pcg for random movement and direction (random generator)
-            let mut pcg_random_move = Pcg::new();
-            let mut pcg_random_direction = Pcg::new();
+        // Create a player status entity (not player entity)
+        io.create_entity()
+            // Add the player status component with the default values
+            .add_component(PlayerStatus::default())
+            // Build the entity
+            .build();
 
-            // Based on the random value, the enemty will move in a random x direction
-            let x = if pcg_random_direction.gen_bool() {
-                pcg_random_move.gen_f32() * 1.
-            } else {
-                pcg_random_move.gen_f32() * -1.
-            };
+        // Create an enemy status entity (not enemy entity)
+        io.create_entity().add_component(EnemyStatus(0.0)).build();
 
-            // Based on the random value, the enemty will move in a random y direction
-            let y = if pcg_random_direction.gen_bool() {
-                pcg_random_move.gen_f32() * 1.
+        // Create the game state entity
+        io.create_entity()
+            // Add the game state component starting on the first stage
+            .add_component(GameState::default())
+            // Build the entity
+            .build();
+
+        // Create a score entity
+        let score_anchor = HudAnchor {
+            region: HudRegion::TopScore,
+            offset: Vec3::new(0., -HUD_LINE_SPACING, 0.),
+        };
+        io.create_entity()
+            // Add the score component with the initial score of 0
+            .add_component(Score::default())
+            // Add the number display centered below the score label
+            .add_component(NumberDisplay::new(
+                playfield.hud_anchor(score_anchor.region) + score_anchor.offset,
+                TextAnchor::Center,
+                SCORE_DIGIT_SPACING,
+                1,
+            ))
+            // Add the HUD anchor component to keep the score in the top score region
+            .add_component(score_anchor)
+            // Build the entity
+            .build();
+
+        // Create the high score entity, which shows the best score from the table or of this game
+        let high_score_anchor = HudAnchor {
+            region: HudRegion::HighScore,
+            offset: Vec3::new(0., -HUD_LINE_SPACING, 0.),
+        };
+        io.create_entity()
+            // Add the high score display component to find the display
+            .add_component(HighScoreDisplay)
+            // Add the number display centered below the high score label
+            .add_component(NumberDisplay::new(
+                playfield.hud_anchor(high_score_anchor.region) + high_score_anchor.offset,
+                TextAnchor::Center,
+                SCORE_DIGIT_SPACING,
+                1,
+            ))
+            // Add the HUD anchor component to keep the high score in the high score region
+            .add_component(high_score_anchor)
+            // Build the entity
+            .build();
+
+        // Create the high score label entity above the high score
+        io.create_entity()
+            // Add the text label component with the label text
+            .add_component(TextLabel::new(
+                "HIGH SCORE",
+                SCORE_LABEL_SIZE,
+                [1.; 3],
+                TextAnchor::Center,
+            ))
+            // Add the transform component with the position of the high score region
+            .add_component(LocalTransform(
+                Transform::default().with_position(playfield.hud_anchor(HudRegion::HighScore)),
+            ))
+            // Add the world transform, which the playfield system places from the local transform
+            .add_component(Transform::default())
+            // Add the HUD anchor component to keep the label in the high score region
+            .add_component(HudAnchor {
+                region: HudRegion::HighScore,
+                offset: Vec3::ZERO,
+            })
+            // Add the synchronized component so that the client can send the glyph meshes
+            .add_component(Synchronized)
+            // Build the entity
+            .build();
+
+        // Create the combo label entity below the score (empty until there is a combo chain)
+        let combo_anchor = HudAnchor {
+            region: HudRegion::TopScore,
+            offset: Vec3::new(0., -2. * HUD_LINE_SPACING, 0.),
+        };
+        io.create_entity()
+            // Add the combo label component to find the label
+            .add_component(ComboLabel)
+            // Add the text label component with no text yet
+            .add_component(TextLabel::new(
+                "",
+                SCORE_LABEL_SIZE,
+                [1.; 3],
+                TextAnchor::Center,
+            ))
+            // Add the transform component with the position below the score digits
+            .add_component(LocalTransform(Transform::default().with_position(
+                playfield.hud_anchor(combo_anchor.region) + combo_anchor.offset,
+            )))
+            // Add the world transform, which the playfield system places from the local transform
+            .add_component(Transform::default())
+            // Add the HUD anchor component to keep the label in the top score region
+            .add_component(combo_anchor)
+            // Add the synchronized component so that the client can send the glyph meshes
+            .add_component(Synchronized)
+            // Build the entity
+            .build();
+
+        // Create the bonus ship notification label entity below the combo label (empty until a bonus ship is given)
+        let extra_life_anchor = HudAnchor {
+            region: HudRegion::TopScore,
+            offset: Vec3::new(0., -3. * HUD_LINE_SPACING, 0.),
+        };
+        io.create_entity()
+            // Add the extra life label component to find the label
+            .add_component(ExtraLifeLabel)
+            // Add the text label component with no text yet
+            .add_component(TextLabel::new(
+                "",
+                SCORE_LABEL_SIZE,
+                [0., 1., 0.],
+                TextAnchor::Center,
+            ))
+            // Add the transform component with the position below the combo label
+            .add_component(LocalTransform(Transform::default().with_position(
+                playfield.hud_anchor(extra_life_anchor.region) + extra_life_anchor.offset,
+            )))
+            // Add the world transform, which the playfield system places from the local transform
+            .add_component(Transform::default())
+            // Add the HUD anchor component to keep the label in the top score region
+            .add_component(extra_life_anchor)
+            // Add the synchronized component so that the client can send the glyph meshes
+            .add_component(Synchronized)
+            // Build the entity
+            .build();
+
+        // Create the score label entity above the score
+        io.create_entity()
+            // Add the text label component with the label text
+            .add_component(TextLabel::new(
+                "SCORE",
+                SCORE_LABEL_SIZE,
+                [1.; 3],
+                TextAnchor::Center,
+            ))
+            // Add the transform component with the position of the top score region
+            .add_component(LocalTransform(
+                Transform::default().with_position(playfield.hud_anchor(HudRegion::TopScore)),
+            ))
+            // Add the world transform, which the playfield system places from the local transform
+            .add_component(Transform::default())
+            // Add the HUD anchor component to keep the label in the top score region
+            .add_component(HudAnchor {
+                region: HudRegion::TopScore,
+                offset: Vec3::ZERO,
+            })
+            // Add the synchronized component so that the client can send the glyph meshes
+            .add_component(Synchronized)
+            // Build the entity
+            .build();
+
+        // Create Player entity with components
+        io.create_entity()
+            // Add the transform component for movement
+            .add_component(LocalTransform(
+                // Add the default transform component
+                Transform::default()
+                    // Set the bottom middle of the screen as the initial position
+                    .with_position(playfield.player_spawn())
+                    // Set the initial rotation to be facing towards to the player based on the camera angle (no needed if you create the object facing a different direction)
+                    .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
+            ))
+            // Add the world transform, which the playfield system places from the local transform
+            .add_component(Transform::default())
+            // Add the render component to draw the player with lines
+            .add_component(Render::new(PLAYER_HANDLE).primitive(Primitive::Lines))
+            // Add the player component as default
+            .add_component(Player::default())
+            // Add the velocity component as standing still
+            .add_component(Velocity::default())
+            // Add the player weapon
+            .add_component(Weapon::player(self.player_hitbox))
+            // Add the player collider
+            .add_component(Collider::player(self.player_hitbox))
+            // Add the synchronized component to synchronize the entity with the client side
+            .add_component(Synchronized)
+            // Build the entity
+            .build();
+
+        // Create Enemy with components
+        io.create_entity()
+            // Add the transform component for movement, firing, and displaying
+            .add_component(LocalTransform(
+                // Add the default transform component
+                Transform::default()
+                    // Set the top middle of the screen as the initial position
+                    .with_position(playfield.enemy_spawn())
+                    // Set the initial rotation to be facing towards to the player based on the camera angle
+                    // (no needed if you create the object facing a different direction or differen angle rotation)
+                    .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
+            ))
+            // Add the world transform, which the playfield system places from the local transform
+            .add_component(Transform::default())
+            // Add the render component to draw the enemy with lines
+            .add_component(Render::new(ENEMY_HANDLE).primitive(Primitive::Lines))
+            // Add the synchronized component to synchronize the entity with the client side
+            .add_component(Synchronized)
+            // Add the enemy component as default
+            .add_component(Enemy::default())
+            // Add the velocity component as standing still
+            .add_component(Velocity::default())
+            // Add the enemy weapon
+            .add_component(Weapon::enemy(self.enemy_hitbox))
+            // Add the enemy collider
+            .add_component(Collider::enemy(self.enemy_hitbox))
+            // Build the entity
+            .build();
+
+        // Create the Window entity with components
+        io.create_entity()
+            // Add the transform component for displaying the window
+            .add_component(LocalTransform(Transform::default()))
+            // Add the world transform, which the playfield system places from the local transform
+            .add_component(Transform::default())
+            // Add the render component to draw the window with lines
+            .add_component(Render::new(WINDOW_SIZE_HANDLE).primitive(Primitive::Lines))
+            // Add the synchronized component to synchronize the entity with the client side
+            .add_component(Synchronized)
+            // Build the entity
+            .build();
+    }
+
+    // The function that will decide whether the gameplay steps on this frame
+    fn fixed_timestep_update(&mut self, io: &mut EngineIo, _query: &mut QueryResult) {
+        // Clear the player input once the ticks of the last frame have used it
+        if self.ticks > 0 {
+            self.move_direction = Vec3::ZERO;
+            self.fire_held = false;
+            self.fire_shot = false;
+        }
+
+        // Do not step unless there is enough frame time for a whole tick
+        self.ticks = 0;
+        self.slot = 0;
+        self.tick = false;
+
+        // Get the FrameTime event
+        let Some(frame_time) = io.inbox_first::<FrameTime>() else { return };
+
+        // Add the frame time to the accumulator; a very slow frame only catches up on so many ticks,
+        // so that the game slows down instead of piling up ticks
+        self.accumulator =
+            (self.accumulator + frame_time.delta).min(MAX_TICKS_PER_FRAME as f32 * FIXED_TIMESTEP);
+
+        // Step the gameplay once for every whole tick that has passed
+        self.ticks = ((self.accumulator / FIXED_TIMESTEP) as u32).min(MAX_TICKS_PER_FRAME);
+    }
+
+    // The function that will decide whether the gameplay systems of the next tick slot step
+    fn tick_slot_update(&mut self, _io: &mut EngineIo, _query: &mut QueryResult) {
+        self.tick = self.slot < self.ticks;
+        self.slot += 1;
+
+        // Move the gameplay forward by the fixed timestep
+        if self.tick {
+            self.accumulator -= FIXED_TIMESTEP;
+            self.time += FIXED_TIMESTEP;
+        }
+    }
+    // The function that will spawn the player
+    fn spawn_player(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+        // For every entity that qualify from the query "Player" will be processed
+        for entity in query.iter("Player") {
+            // If the player is dead
+            if !(query.read::<PlayerStatus>(entity).status) {
+                // Read the time when the PlayerStatus componenet
+                let mut dead_time = query.read::<PlayerStatus>(entity).dead_time;
+                // If the player just died
+                if dead_time == 0.0 {
+                    // Record the dead time to the current time
+                    dead_time = self.time;
+                }
+                // If the player has been dead for a certain amount of time (PLAYER_SPAWN_TIME) and the game is being played
+                let playing = query
+                    .iter("Game_State")
+                    .all(|id| query.read::<GameState>(id).phase == GamePhase::Playing);
+                if dead_time + PLAYER_SPAWN_TIME < self.time && playing {
+                    // Recreate the player entity
+                    io.create_entity()
+                        .add_component(LocalTransform(
+                            Transform::default()
+                                .with_position(self.playfield.player_spawn())
+                                .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
+                        ))
+                        // Add the world transform, which the playfield system places from the local transform
+                        .add_component(Transform::default())
+                        .add_component(Render::new(PLAYER_HANDLE).primitive(Primitive::Lines))
+                        .add_component(Player::default())
+                        .add_component(Velocity::default())
+                        .add_component(Weapon::player(self.player_hitbox))
+                        .add_component(Collider::player(self.player_hitbox))
+                        .add_component(Synchronized)
+                        .build();
+                    // Throw away the timer entity (PlayerStatus)
+                    io.remove_entity(entity);
+                    // Recreate the PlayerStatus entity with the default value
+                    io.create_entity()
+                        .add_component(PlayerStatus::default())
+                        .build();
+                }
+                // Otherwise, update the dead time on the PlayerStatus entity
+                else {
+                    query.modify::<PlayerStatus>(entity, |value| {
+                        value.dead_time = dead_time;
+                    })
+                }
+            }
+        }
+    }
+    // The function that will spawn the enemy
+    fn spawn_enemy(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // If there are less enemy entities on the screen than the max enemy count from the query "Enemy_Count"
+        if (query.iter("Enemy_Count").count() as u32) < ENEMY_COUNT {
+            // For every entity that qualify from the query "Enemy_Status" will be processed
+            for entity in query.iter("Enemy_Status") {
+                // Read the dead time from the EnemyStatus component
+                let mut dead_time = query.read::<EnemyStatus>(entity).0;
+
+                // If the enemy just died
+                if dead_time == 0.0 {
+                    // Record the dead time of the enemy to the current time
+                    dead_time = self.time;
+                }
+
+                // If the enemy has been dead for a certain amount of time (ENEMY_SPAWN_TIME)
+                if dead_time + ENEMY_SPAWN_TIME < self.time {
+                    // Pick the kind of the new enemy at random
+                    let mut pcg = Pcg::new();
+                    let kind = if pcg.gen_f32() < BOSS_CHANCE {
+                        EnemyKind::Boss
+                    } else if pcg.gen_bool() {
+                        EnemyKind::Butterfly
+                    } else {
+                        EnemyKind::Bee
+                    };
+
+                    // Recreate the enemy entity
+                    io.create_entity()
+                        .add_component(LocalTransform(
+                            Transform::default()
+                                .with_position(self.playfield.enemy_spawn())
+                                .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
+                        ))
+                        // Add the world transform, which the playfield system places from the local transform
+                        .add_component(Transform::default())
+                        .add_component(Render::new(ENEMY_HANDLE).primitive(Primitive::Lines))
+                        .add_component(Synchronized)
+                        .add_component(Enemy::new(kind))
+                        .add_component(Velocity::default())
+                        .add_component(Weapon::enemy(self.enemy_hitbox))
+                        .add_component(Collider::enemy(self.enemy_hitbox))
+                        .build();
+                    // Throw away the timer entity (EnemyStatus)
+                    io.remove_entity(entity);
+                    // Recreate the EnemyStatus entity with the default value
+                    io.create_entity().add_component(EnemyStatus(0.0)).build();
+                }
+                // Otherwise, update the dead time on the EnemyStatus entity
+                else {
+                    query.modify::<EnemyStatus>(entity, |value| {
+                        value.0 = dead_time;
+                    })
+                }
+            }
+        }
+    }
+    // The function that will keep the MoveCommand events of this frame for the player movement
+    fn player_move_command_update(&mut self, io: &mut EngineIo, _query: &mut QueryResult) {
+        // Add up every MoveCommand event received from the client on this frame
+        let direction = io
+            .inbox::<MoveCommand>()
+            .fold(Vec3::ZERO, |direction, MoveCommand(command)| {
+                direction + command
+            });
+
+        // Keep the latest direction for the ticks (the commands also come in on the frames without a tick)
+        if direction != Vec3::ZERO {
+            self.move_direction = direction.normalize_or_zero();
+        }
+    }
+
+    // The function that will handle the player movement
+    fn player_movement_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // Every tick of the frame moves the same way; the player stands still once the commands stop coming
+        let direction = self.move_direction;
+
+        // For every entity that qualify from the query "Player_Movement" will be processed
+        for entity in query.iter("Player_Movement") {
+            // Set the player velocity based on the direction and the player speed
+            let mut velocity = direction * PLAYER_SPEED;
+
+            // Set the limit of the player movement
+            let x_limit = self.playfield.size.x / 2.0;
+            // Read the player hitbox on the next position
+            let collider = query.read::<Collider>(entity);
+            let hitbox_x = query.read::<LocalTransform>(entity).0.pos.x
+                + velocity.x * FIXED_TIMESTEP
+                + collider.offset.x;
+            // If the player is about to go out of bound, do not move the player
+            if hitbox_x - collider.size.x / 2. < -x_limit
+                || hitbox_x + collider.size.x / 2. > x_limit
+            {
+                velocity = Vec3::ZERO;
+            }
+
+            // Update the player velocity
+            query.modify::<Velocity>(entity, |value| {
+                value.0 = velocity;
+            });
+        }
+    }
+
+    // The function that will handle the enemy movement
+    fn enemy_movement_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // For every entity that qualify from the query "Enemy_Movement" will be processed
+        for entity in query.iter("Enemy_Movement") {
+            // Read the enemy and its current position
+            let mut enemy = query.read::<Enemy>(entity);
+            let current_position = query.read::<LocalTransform>(entity).0.pos;
+
+            // A diving enemy flies straight down
+            if enemy.state == EnemyState::Diving {
+                // Once it passes the bottom of the window, send it back to the formation at the top
+                if current_position.y < -self.playfield.size.y / 2. {
+                    let spawn = self.playfield.enemy_spawn();
+                    query.modify::<LocalTransform>(entity, |value| {
+                        value.0.pos = Vec3::new(current_position.x, spawn.y, current_position.z);
+                    });
+                    enemy.state = EnemyState::Formation;
+                    enemy.escorting = false;
+                    query.modify::<Enemy>(entity, |value| *value = enemy);
+                    query.modify::<Velocity>(entity, |value| {
+                        value.0 = Vec3::ZERO;
+                    });
+                } else {
+                    query.modify::<Velocity>(entity, |value| {
+                        value.0 = Vec3::new(0., -ENEMY_DIVE_SPEED, 0.);
+                    });
+                }
+                continue;
+            }
+
+            // Set pcg for random movement and direction (random generator)
+            let mut pcg_random_move = Pcg::new();
+            let mut pcg_random_direction = Pcg::new();
+
+            // Based on the random value, the enemy in the formation may start a dive
+            if pcg_random_move.gen_f32() < ENEMY_DIVE_CHANCE * FIXED_TIMESTEP {
+                enemy.state = EnemyState::Diving;
+                query.modify::<Enemy>(entity, |value| *value = enemy);
+
+                // A boss takes some of the formation with it as escorts
+                if enemy.kind == EnemyKind::Boss {
+                    let mut escorts = 0;
+                    for other in query.iter("Enemy_Movement") {
+                        let escort = query.read::<Enemy>(other);
+                        if escorts < BOSS_MAX_ESCORTS
+                            && other != entity
+                            && escort.kind != EnemyKind::Boss
+                            && escort.state == EnemyState::Formation
+                        {
+                            query.modify::<Enemy>(other, |value| {
+                                value.state = EnemyState::Diving;
+                                value.leader = entity;
+                                value.escorting = true;
+                            });
+                            escorts += 1;
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // Based on the random value, the enemty will move in a random x direction
+            let x = if pcg_random_direction.gen_bool() {
+                pcg_random_move.gen_f32() * 1.
+            } else {
+                pcg_random_move.gen_f32() * -1.
+            };
+
+            // Based on the random value, the enemty will move in a random y direction
+            let y = if pcg_random_direction.gen_bool() {
+                pcg_random_move.gen_f32() * 1.
             } else {
                 pcg_random_move.gen_f32() * -1.
             };
@@ -996,382 +3260,2285 @@ impl ServerState {
             // Declare the enemy direction that will be used for the next frame
             let speed = Vec3::new(x, y, 0.);
 
-            // Update the enemy speed based on the frame_time delta value
-            let direction = speed.normalize() * frame_time.delta * ENEMY_SPEED;
+            // Set the enemy velocity based on the enemy speed
+            let mut velocity = speed.normalize() * ENEMY_SPEED;
+
+            // Update the enemy movement based on the fixed timestep
+            let direction = velocity * FIXED_TIMESTEP;
 
             // Declare the out of bound limits
-            let x_limit = WITDH / 2.0;
-            let y_upper_limit = HEIGHT / 2.;
-            let y_limit = HEIGHT / 5.;
+            let x_limit = self.playfield.size.x / 2.0;
+            let y_upper_limit = self.playfield.size.y / 2.;
+            let y_limit = self.playfield.size.y / 5.;
 
-            // Read the current enemy position
-            let current_position = query.read::<Enemy>(entity).current_position;
+            // Read the enemy hitbox
+            let collider = query.read::<Collider>(entity);
+            let hitbox_x = current_position.x + direction.x + collider.offset.x;
 
-            // If the enemy is about to go out of bound
-            if (current_position.x + direction.x - ENEMY_SIZE < -x_limit)
-                || (current_position.x + direction.x + ENEMY_SIZE > x_limit)
+            // If the enemy is about to go out of bound, do not move the enemy
+            if (hitbox_x - collider.size.x / 2. < -x_limit)
+                || (hitbox_x + collider.size.x / 2. > x_limit)
                 || (current_position.y + direction.y >= y_upper_limit)
                 || (current_position.y + direction.y < y_limit)
             {
-                // Do not move the enemy and conclude the function
-                return;
+                velocity = Vec3::ZERO;
             }
-            // Otherwise, move the enemy
-            query.modify::<Transform>(entity, |transform| {
-                transform.pos += direction;
+
+            // Update the enemy velocity
+            query.modify::<Velocity>(entity, |value| {
+                value.0 = velocity;
+            });
+        }
+    }
+
+    // The function that will keep the FireCommand events of this frame for the player fire
+    fn player_fire_update(&mut self, io: &mut EngineIo, _query: &mut QueryResult) {
+        // Read every FireCommand event received from the client
+        let commands: Vec<FireCommand> = io.inbox().collect();
+
+        // If fire is held, keep it held for every tick, and check whether the client asked for a shot on this frame
+        if !commands.is_empty() {
+            self.fire_held = true;
+            self.fire_shot |= commands.iter().any(|FireCommand(shot)| *shot);
+        }
+    }
+
+    // The function that will pull the trigger of the player weapon on every tick from the fire input of this frame
+    fn player_trigger_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick || !self.fire_held {
+            return;
+        }
+
+        // For every entity that qualify from the query "Player_Fire_Input" will be processed
+        for entity in query.iter("Player_Fire_Input") {
+            // Pull the trigger of the laser on every tick while fire is held, and of the other weapons once for each shot asked for
+            let kind = query.read::<Weapon>(entity).kind;
+            if kind == WeaponKind::Laser || self.fire_shot {
+                query.modify::<Weapon>(entity, |weapon| {
+                    weapon.trigger = true;
+                });
+            }
+        }
+        self.fire_shot = false;
+    }
+
+    // The function that will switch the player weapon to the next weapon kind
+    fn player_weapon_switch_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // If the SwitchWeaponCommand event is received from the client
+        if io.inbox_first::<SwitchWeaponCommand>().is_some() {
+            // For every entity that qualify from the query "Player_Weapon" will be processed
+            for entity in query.iter("Player_Weapon") {
+                query.modify::<Weapon>(entity, |weapon| {
+                    // Keep the bullets that are still on screen counted against the new weapon,
+                    // and keep the laser energy and the time until the next shot so that switching does not refill them
+                    let bullet_count = weapon.bullet_count;
+                    let energy = weapon.energy;
+                    let cooldown_timer = weapon.cooldown_timer;
+                    *weapon = Weapon::player_kind(weapon.kind.next(), self.player_hitbox);
+                    weapon.bullet_count = bullet_count;
+                    weapon.energy = energy.min(weapon.energy_max);
+                    weapon.cooldown_timer = cooldown_timer;
+                });
+            }
+        }
+    }
+
+    // The function that will handle the enemy fire update
+    fn enemy_fire_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // Set the random generator for the enemy fire
+        let mut pcg_fire = Pcg::new();
+
+        // For every entity that qualify from the query "Enemy_Fire_Input" will be processed
+        for entity in query.iter("Enemy_Fire_Input") {
+            // If the random generator return true to fire
+            if pcg_fire.gen_bool() {
+                // Pull the trigger of the enemy weapon
+                query.modify::<Weapon>(entity, |weapon| {
+                    weapon.trigger = true;
+                });
+            }
+        }
+    }
+
+    // The function that will fire the bullets from every weapon whose trigger was pulled
+    fn weapon_fire_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // Set the random generator for the indestructible bullets
+        let mut pcg_indestructible = Pcg::new();
+
+        // For every entity that qualify from the query "Weapon" will be processed
+        for entity in query.iter("Weapon") {
+            // Read the owner position and the weapon
+            let position = query.read::<LocalTransform>(entity).0.pos;
+            let mut weapon = query.read::<Weapon>(entity);
+
+            // Count down the time until the next shot
+            weapon.cooldown_timer = (weapon.cooldown_timer - FIXED_TIMESTEP).max(0.0);
+
+            // If the trigger was pulled and the weapon is ready to fire again (the laser is fired from the laser beam system)
+            if weapon.trigger && weapon.cooldown_timer <= 0.0 && weapon.kind != WeaponKind::Laser {
+                // Pick the muzzles that fire on this shot based on the weapon pattern
+                let muzzles = match weapon.pattern {
+                    WeaponPattern::Volley => 0..weapon.muzzle_count,
+                    WeaponPattern::Alternate => weapon.next_muzzle..weapon.next_muzzle + 1,
+                };
+
+                // For every muzzle that fires on this shot
+                for muzzle in muzzles {
+                    // If the weapon already has the max bullet count on screen, stop firing
+                    if weapon.bullet_count >= weapon.ammo_cap {
+                        break;
+                    }
+
+                    // Increase the bullet count that are on screen from that weapon by 1
+                    weapon.bullet_count += 1;
+
+                    // Count the shots of the player for the results screen
+                    if weapon.from_player {
+                        for game in query.iter("Game_State") {
+                            query.modify::<GameState>(game, |value| value.stats.shots += 1);
+                        }
+                    }
+
+                    // Based on the random value, the bullet can not be cancelled by player bullets
+                    let indestructible =
+                        pcg_indestructible.gen_f32() < weapon.indestructible_chance;
+                    let mesh = if indestructible {
+                        weapon.indestructible_mesh
+                    } else {
+                        weapon.bullet_mesh
+                    };
+
+                    // Create the bullet entity from the muzzle position
+                    let bullet = io
+                        .create_entity()
+                        // Add the render component as triangle
+                        .add_component(Render::new(mesh).primitive(Primitive::Triangles))
+                        // Add the synchronized component
+                        .add_component(Synchronized)
+                        // Add the bullet component that is from the weapon owner (player or enemy entity)
+                        .add_component(Bullet {
+                            entity_id: entity,
+                            damage: weapon.damage,
+                            indestructible,
+                        })
+                        // Add the velocity component to fly in the weapon direction
+                        .add_component(Velocity(weapon.direction * weapon.bullet_speed))
+                        // Add the collider component of the bullet
+                        .add_component(weapon.bullet_collider)
+                        // Add the transform component with the position based on the owner position + muzzle offset
+                        .add_component(LocalTransform(
+                            Transform::default()
+                                .with_position(position + weapon.muzzles[muzzle as usize]),
+                        ))
+                        // Add the world transform, which the playfield system places from the local transform
+                        .add_component(Transform::default())
+                        // Remove the bullet once it leaves the window
+                        .add_component(DespawnOutsidePlayfield(BULLET_DESPAWN_INSET))
+                        // Build the entity
+                        .build();
+
+                    // If the weapon fires missiles, make the bullet home in on the enemy until it runs out of time
+                    if weapon.kind == WeaponKind::Missile {
+                        io.add_component(bullet, Missile::default());
+                        io.add_component(bullet, Lifetime(MISSILE_LIFETIME));
+                    }
+                }
+
+                // Move on to the next muzzle for the alternate pattern
+                if weapon.pattern == WeaponPattern::Alternate {
+                    weapon.next_muzzle = (weapon.next_muzzle + 1) % weapon.muzzle_count.max(1);
+                }
+
+                // Restart the time until the next shot
+                weapon.cooldown_timer = weapon.cooldown;
+            }
+
+            // Release the trigger; the fire systems pull it again on the next tick
+            weapon.trigger = false;
+
+            // Update the weapon
+            query.modify::<Weapon>(entity, |value| {
+                *value = weapon;
+            });
+        }
+    }
+
+    // The function that will steer the missiles toward the nearest enemy
+    fn missile_homing_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // For every entity that qualify from the query "Missile" will be processed
+        for entity in query.iter("Missile") {
+            let mut missile = query.read::<Missile>(entity);
+            let position = query.read::<LocalTransform>(entity).0.pos;
+
+            // If the missile has no target or the target was destroyed, pick the nearest enemy
+            if !missile.has_target || !query.iter("Missile_Target").any(|id| id == missile.target) {
+                missile.has_target = false;
+                let mut nearest_distance = f32::MAX;
+                for enemy in query.iter("Missile_Target") {
+                    let distance = query.read::<LocalTransform>(enemy).0.pos.distance(position);
+                    if distance < nearest_distance {
+                        nearest_distance = distance;
+                        missile.target = enemy;
+                        missile.has_target = true;
+                    }
+                }
+            }
+
+            // Read the current missile velocity
+            let Velocity(mut velocity) = query.read::<Velocity>(entity);
+
+            // If there is a target, turn toward it no faster than the turn rate
+            if missile.has_target {
+                let to_target = query.read::<LocalTransform>(missile.target).0.pos - position;
+                let heading = velocity.y.atan2(velocity.x);
+                let wanted_heading = to_target.y.atan2(to_target.x);
+
+                // Get the shortest signed angle between the current heading and the wanted heading
+                let mut turn = wanted_heading - heading;
+                if turn > PI {
+                    turn -= 2. * PI;
+                } else if turn < -PI {
+                    turn += 2. * PI;
+                }
+
+                let max_turn = missile.turn_rate * FIXED_TIMESTEP;
+                velocity = Quat::from_rotation_z(turn.clamp(-max_turn, max_turn)) * velocity;
+            }
+
+            // Update the missile velocity
+            query.modify::<Velocity>(entity, |value| {
+                value.0 = velocity;
             });
-            // Update the new enemy position
-            query.modify::<Enemy>(entity, |enemy| {
-                enemy.current_position += direction;
+
+            // Point the missile toward where it is flying (the missile mesh points up)
+            query.modify::<LocalTransform>(entity, |LocalTransform(transform)| {
+                transform.orient = Quat::from_rotation_z(velocity.y.atan2(velocity.x) - PI / 2.);
+            });
+
+            // Update the missile
+            query.modify::<Missile>(entity, |value| {
+                *value = missile;
+            });
+        }
+    }
+
+    // The function that will fire the laser beam from the player while the trigger is held
+    fn laser_beam_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // For every entity that qualify from the query "Laser" will be processed
+        for entity in query.iter("Laser") {
+            let mut weapon = query.read::<Weapon>(entity);
+            let position = query.read::<LocalTransform>(entity).0.pos;
+
+            // Find the beam and the energy meter of this player
+            let beam = query
+                .iter("Laser_Beam")
+                .find(|id| query.read::<LaserBeam>(*id).owner == entity);
+            let meter = query
+                .iter("Energy_Meter")
+                .find(|id| query.read::<EnergyMeter>(*id).owner == entity);
+
+            // If the player switched away from the laser, remove the beam and the energy meter
+            if weapon.kind != WeaponKind::Laser {
+                if let Some(beam) = beam {
+                    io.remove_entity(beam);
+                }
+                if let Some(meter) = meter {
+                    io.remove_entity(meter);
+                }
+                continue;
+            }
+
+            // Fire while the trigger is held and there is energy left; otherwise recharge the energy
+            let firing = weapon.trigger && weapon.energy > 0.0;
+            if firing {
+                weapon.energy = (weapon.energy - LASER_ENERGY_DRAIN * FIXED_TIMESTEP).max(0.0);
+            } else {
+                weapon.energy =
+                    (weapon.energy + LASER_ENERGY_RECHARGE * FIXED_TIMESTEP).min(weapon.energy_max);
+            }
+
+            if firing {
+                // The beam starts from the muzzle and goes up to the top of the window
+                let origin = position + weapon.muzzles[0];
+                let mut length = self.playfield.size.y / 2. - origin.y;
+                let mut hit_enemy = None;
+
+                // Find the first enemy that the beam goes through
+                for enemy in query.iter("Laser_Target") {
+                    let collider = query.read::<Collider>(enemy);
+                    let (half_size, radius) = collider.rounded_box();
+                    if let Some(distance) = ray_rounded_box_intersection(
+                        origin,
+                        weapon.direction,
+                        query.read::<LocalTransform>(enemy).0.pos + collider.offset,
+                        half_size,
+                        radius,
+                    ) {
+                        if distance < length && query.read::<Enemy>(enemy).health > 0.0 {
+                            length = distance;
+                            hit_enemy = Some(enemy);
+                        }
+                    }
+                }
+
+                // Damage the enemy over time
+                if let Some(enemy) = hit_enemy {
+                    // Work out what the enemy is worth before the hit, while it is still diving or in the formation
+                    let target = query.read::<Enemy>(enemy);
+                    let escorts = count_escorts(query, "Laser_Target", enemy);
+                    let destroyed = damage_enemy(io, query, enemy, weapon.damage * FIXED_TIMESTEP);
+                    io.send(&HitEvent {
+                        target: enemy,
+                        target_kind: HitKind::Enemy(target.kind),
+                        source: entity,
+                        source_kind: HitKind::PlayerLaser,
+                        destroyed,
+                        points: if destroyed {
+                            self.scoring.points(&target, escorts)
+                        } else {
+                            0
+                        },
+                        position: query.read::<LocalTransform>(enemy).0.pos,
+                    });
+                }
+
+                // Only draw the beam segments up to the enemy that was hit
+                let limit = (length.max(0.0) / LASER_SEGMENT_LENGTH).ceil() as u32 * 2;
+
+                match beam {
+                    // Move the beam to the muzzle and update the length
+                    Some(beam) => {
+                        query.modify::<LocalTransform>(beam, |LocalTransform(transform)| {
+                            transform.pos = origin;
+                        });
+                        query.modify::<Render>(beam, |render| {
+                            render.limit = Some(limit);
+                        });
+                    }
+                    // Create the beam entity from the muzzle
+                    None => {
+                        let mut render =
+                            Render::new(weapon.bullet_mesh).primitive(Primitive::Lines);
+                        render.limit = Some(limit);
+                        io.create_entity()
+                            .add_component(render)
+                            .add_component(Synchronized)
+                            .add_component(LaserBeam { owner: entity })
+                            .add_component(LocalTransform(
+                                Transform::default().with_position(origin),
+                            ))
+                            // Add the world transform, which the playfield system places from the local transform
+                            .add_component(Transform::default())
+                            .build();
+                    }
+                }
+            } else if let Some(beam) = beam {
+                // Remove the beam when the laser stops firing
+                io.remove_entity(beam);
+            }
+
+            // Only draw the energy meter segments for the energy left
+            let limit = (weapon.energy / weapon.energy_max * ENERGY_METER_SEGMENTS as f32).ceil()
+                as u32
+                * 2;
+
+            match meter {
+                // Update the energy meter length
+                Some(meter) => {
+                    query.modify::<Render>(meter, |render| {
+                        render.limit = Some(limit);
+                    });
+                }
+                // Create the energy meter entity below the bottom left of the window (under the reserve ships)
+                None => {
+                    let mut render = Render::new(ENERGY_METER_HANDLE).primitive(Primitive::Lines);
+                    render.limit = Some(limit);
+                    let anchor = HudAnchor {
+                        region: HudRegion::ReserveShips,
+                        offset: Vec3::new(-HUD_MARGIN, -HUD_MARGIN - ENERGY_METER_GAP, 0.),
+                    };
+                    io.create_entity()
+                        .add_component(render)
+                        .add_component(Synchronized)
+                        .add_component(EnergyMeter { owner: entity })
+                        .add_component(LocalTransform(Transform::default().with_position(
+                            self.playfield.hud_anchor(anchor.region) + anchor.offset,
+                        )))
+                        // Add the world transform, which the playfield system places from the local transform
+                        .add_component(Transform::default())
+                        // Add the HUD anchor component to keep the meter with the reserve ships
+                        .add_component(anchor)
+                        .build();
+                }
+            }
+
+            // Update the weapon
+            query.modify::<Weapon>(entity, |value| {
+                *value = weapon;
             });
         }
+
+        // Remove the beams and the energy meters of the players that are gone
+        for beam in query.iter("Laser_Beam") {
+            let owner = query.read::<LaserBeam>(beam).owner;
+            if !query.iter("Laser").any(|id| id == owner) {
+                io.remove_entity(beam);
+            }
+        }
+        for meter in query.iter("Energy_Meter") {
+            let owner = query.read::<EnergyMeter>(meter).owner;
+            if !query.iter("Laser").any(|id| id == owner) {
+                io.remove_entity(meter);
+            }
+        }
+    }
+
+    // The function that will move every entity based on its velocity
+    fn velocity_integration_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // For every entity that qualify from the query "Velocity" will be processed
+        for entity in query.iter("Velocity") {
+            // Move the entity by its velocity over this frame
+            let Velocity(velocity) = query.read::<Velocity>(entity);
+            query.modify::<LocalTransform>(entity, |LocalTransform(transform)| {
+                transform.pos += velocity * FIXED_TIMESTEP;
+            });
+        }
+    }
+
+    // The function that will remove every entity that ran out of time or left the window
+    fn cleanup_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // Keep track of the entities to remove on this tick
+        let mut expired: Vec<EntityId> = Vec::new();
+
+        // For every entity that qualify from the query "Lifetime" will be processed
+        for entity in query.iter("Lifetime") {
+            // Count down the time left, and remove the entity once it runs out
+            query.modify::<Lifetime>(entity, |value| {
+                value.0 -= FIXED_TIMESTEP;
+            });
+            if query.read::<Lifetime>(entity).0 <= 0.0 {
+                expired.push(entity);
+            }
+        }
+
+        // For every entity that qualify from the query "Despawn_Outside_Playfield" will be processed
+        for entity in query.iter("Despawn_Outside_Playfield") {
+            // Remove the entity once it goes past the top or bottom window edge (less the inset), or past the side edges
+            // (the inset is only on the way the bullets fly, so the bullets from the window sides are not removed right away)
+            let DespawnOutsidePlayfield(inset) = query.read::<DespawnOutsidePlayfield>(entity);
+            let limit = self.playfield.size / 2. - Vec3::new(0., inset, 0.);
+            let position = query.read::<LocalTransform>(entity).0.pos;
+            // (an entity can both run out of time and leave the window, but is only removed once)
+            if (position.x.abs() > limit.x || position.y.abs() > limit.y)
+                && !expired.contains(&entity)
+            {
+                expired.push(entity);
+            }
+        }
+
+        // Remove every expired entity
+        for entity in expired {
+            // Bullets also release their spot in the bullet count of the weapon that fired them
+            if query.iter("Bullet_Owner").any(|id| id == entity) {
+                remove_bullet(io, query, "Bullet_Count_Update", entity);
+            } else {
+                io.remove_entity(entity);
+            }
+        }
+    }
+
+    // The function that will find every pair of colliders that hit each other
+    fn collision_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+        // Forget the collisions from the last tick
+        self.collisions.clear();
+
+        // Read how far every moving entity moved on this frame from the query "Moving"
+        let movements: HashMap<EntityId, Vec3> = query
+            .iter("Moving")
+            .map(|entity| (entity, query.read::<Velocity>(entity).0 * FIXED_TIMESTEP))
+            .collect();
+
+        // Read the start position, the current position and the collider of every entity that qualify from the query "Collider"
+        // (moving entities start where they were before the movement on this frame, so that their whole path is checked)
+        let colliders: Vec<(EntityId, Vec3, Vec3, Collider)> = query
+            .iter("Collider")
+            .map(|entity| {
+                let collider = query.read::<Collider>(entity);
+                let position = query.read::<LocalTransform>(entity).0.pos + collider.offset;
+                let start = position - movements.get(&entity).copied().unwrap_or(Vec3::ZERO);
+                (entity, start, position, collider)
+            })
+            .collect();
+
+        // Put every collider into the grid cells that its hitbox covers along its path on this frame
+        let mut grid =
+            SpatialGrid::new(self.playfield.size.x, self.playfield.size.y, GRID_CELL_SIZE);
+        for (i, (_, start, position, collider)) in colliders.iter().enumerate() {
+            let half_size = collider.size / 2.;
+            grid.insert(
+                i,
+                start.min(*position) - half_size,
+                start.max(*position) + half_size,
+            );
+        }
+
+        // Only check the pairs of colliders that share a grid cell
+        for (i, j) in grid.candidate_pairs() {
+            let (entity1, start1, position1, collider1) = colliders[i];
+            let (entity2, start2, position2, collider2) = colliders[j];
+
+            // If neither collider collides with the layer of the other one, skip the pair
+            if collider1.mask & collider2.layer == 0 && collider2.mask & collider1.layer == 0 {
+                continue;
+            }
+
+            // If the two hitboxes intersect (anywhere along their paths if either one moved), record the collision pair
+            let hit = if start1 == position1 && start2 == position2 {
+                collision_detection(position1, &collider1, position2, &collider2)
+            } else {
+                swept_collision_detection(
+                    start1, position1, &collider1, start2, position2, &collider2,
+                )
+            };
+
+            if hit {
+                self.collisions.push((entity1, entity2));
+            }
+        }
+    }
+
+    // The function that will react to the collision pairs found on this frame
+    fn collision_response_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Only step the gameplay on a fixed tick
+        if !self.tick {
+            return;
+        }
+
+        // Keep track of the entities that were already removed on this frame
+        let mut removed: Vec<EntityId> = Vec::new();
+
+        // For every collision pair found on this frame
+        for (entity1, entity2) in std::mem::take(&mut self.collisions) {
+            // If either entity was already removed by another collision, skip the pair
+            if removed.contains(&entity1) || removed.contains(&entity2) {
+                continue;
+            }
+
+            // Order the pair by layer so that each kind of collision only needs to be handled once
+            let layer1 = query.read::<Collider>(entity1).layer;
+            let layer2 = query.read::<Collider>(entity2).layer;
+            let (entity1, entity2, layer1, layer2) = if layer1 <= layer2 {
+                (entity1, entity2, layer1, layer2)
+            } else {
+                (entity2, entity1, layer2, layer1)
+            };
+
+            match (layer1, layer2) {
+                // If the enemy bullet hit the player
+                (LAYER_PLAYER, LAYER_ENEMY_BULLET) => {
+                    // Update the bullet count from that enemy to generate more bullets and remove the bullet entity
+                    remove_bullet(io, query, "Bullet_Count_Update", entity2);
+                    // Remove the player entity
+                    io.remove_entity(entity1);
+                    removed.extend([entity1, entity2]);
+                    // For every entity that qualify from the query "Player_Status_Update" will be processed
+                    for entity in query.iter("Player_Status_Update") {
+                        // Set the player status as dead
+                        query.modify::<PlayerStatus>(entity, |value| {
+                            value.status = false;
+                        });
+                    }
+                    // Let everything else react to the player getting destroyed
+                    io.send(&HitEvent {
+                        target: entity1,
+                        target_kind: HitKind::Player,
+                        source: entity2,
+                        source_kind: HitKind::EnemyBullet,
+                        destroyed: true,
+                        points: 0,
+                        position: query.read::<LocalTransform>(entity1).0.pos,
+                    });
+                }
+                // If the player bullet hit the enemy
+                (LAYER_ENEMY, LAYER_PLAYER_BULLET) => {
+                    // Damage the enemy with the bullet damage
+                    let damage = query.read::<Bullet>(entity2).damage;
+                    // Remove the bullet entity
+                    remove_bullet(io, query, "Bullet_Count_Update", entity2);
+                    removed.push(entity2);
+                    // Work out what the enemy is worth before the hit, while it is still diving or in the formation
+                    let target = query.read::<Enemy>(entity1);
+                    let escorts = count_escorts(query, "Enemy", entity1);
+                    // Damage the enemy entity and remove it if it was destroyed
+                    let destroyed = damage_enemy(io, query, entity1, damage);
+                    if destroyed {
+                        removed.push(entity1);
+                    }
+                    // Let everything else react to the enemy getting hit
+                    io.send(&HitEvent {
+                        target: entity1,
+                        target_kind: HitKind::Enemy(target.kind),
+                        source: entity2,
+                        source_kind: HitKind::PlayerBullet,
+                        destroyed,
+                        points: if destroyed {
+                            self.scoring.points(&target, escorts)
+                        } else {
+                            0
+                        },
+                        position: query.read::<LocalTransform>(entity1).0.pos,
+                    });
+                }
+                // If the player bullet hit the enemy bullet
+                (LAYER_PLAYER_BULLET, LAYER_ENEMY_BULLET) => {
+                    // Remove the player bullet entity
+                    remove_bullet(io, query, "Bullet_Count_Update", entity1);
+                    removed.push(entity1);
+                    // Unless the enemy bullet is indestructible, decrease the bullet count from that enemy and remove the enemy bullet entity
+                    let destroyed = !query.read::<Bullet>(entity2).indestructible;
+                    if destroyed {
+                        remove_bullet(io, query, "Bullet_Count_Update", entity2);
+                        removed.push(entity2);
+                    }
+                    // Let everything else react to the bullets cancelling out
+                    io.send(&HitEvent {
+                        target: entity2,
+                        target_kind: HitKind::EnemyBullet,
+                        source: entity1,
+                        source_kind: HitKind::PlayerBullet,
+                        destroyed,
+                        points: 0,
+                        position: query.read::<LocalTransform>(entity2).0.pos,
+                    });
+                }
+                // Any other pair has no reaction
+                _ => {}
+            }
+        }
+    }
+
+    // The function that will update the score from the hits
+    fn score_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // When a HitEvent event is received (the hits are not tick gated, so none of them are missed)
+        for hit in io.inbox::<HitEvent>() {
+            // The points added by this hit
+            let mut awarded = 0;
+
+            // For every entity that qualify from the query "Score_Update" will be processed
+            for entity in query.iter("Score_Update") {
+                query.modify::<Score>(entity, |value| match hit.target_kind {
+                    // When an enemy is destroyed, raise the multiplier if the last kill was within the combo window,
+                    // and increase the score by the points of the enemy times the multiplier
+                    HitKind::Enemy(_) if hit.destroyed => {
+                        value.multiplier = if value.combo_timer > 0.0 {
+                            (value.multiplier + 1).min(COMBO_MAX_MULTIPLIER)
+                        } else {
+                            1
+                        };
+                        value.combo_timer = COMBO_WINDOW;
+                        awarded = hit.points * value.multiplier;
+                        value.score += awarded;
+                    }
+                    // When the player is hit, the combo chain is broken
+                    HitKind::Player => {
+                        value.multiplier = 1;
+                        value.combo_timer = 0.0;
+                    }
+                    _ => {}
+                });
+            }
+
+            // Let everything else show the points the kill was worth
+            if awarded > 0 {
+                io.send(&ScoreEvent {
+                    points: awarded,
+                    position: hit.position,
+                });
+            }
+        }
+    }
+
+    // The function that will show the points of every kill where the enemy died, drifting up and blinking out
+    fn score_popup_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // When a ScoreEvent event is received, create a popup with the points earned
+        for ScoreEvent { points, position } in io.inbox::<ScoreEvent>() {
+            let size = if points >= POPUP_BIG_POINTS {
+                POPUP_BIG_SIZE
+            } else {
+                POPUP_SIZE
+            };
+            io.create_entity()
+                .add_component(ScorePopup { points })
+                .add_component(TextLabel::new(
+                    &points.to_string(),
+                    size,
+                    [1., 1., 0.],
+                    TextAnchor::Center,
+                ))
+                .add_component(LocalTransform(Transform::default().with_position(position)))
+                // Add the world transform, which the playfield system places from the local transform
+                .add_component(Transform::default())
+                // Drift upward until the cleanup system removes it
+                .add_component(Velocity(Vec3::new(0., POPUP_SPEED, 0.)))
+                .add_component(Lifetime(POPUP_LIFETIME))
+                .add_component(Synchronized)
+                .build();
+        }
+
+        // Blink the popups out over their last seconds
+        for entity in query.iter("Score_Popup") {
+            let lifetime = query.read::<Lifetime>(entity).0;
+            let text = if lifetime > POPUP_BLINK_TIME || (lifetime * 10.) as u32 % 2 == 0 {
+                query.read::<ScorePopup>(entity).points.to_string()
+            } else {
+                String::new()
+            };
+            if query.read::<TextLabel>(entity).characters() != text.as_bytes() {
+                query.modify::<TextLabel>(entity, |value| value.set_text(&text));
+            }
+        }
+    }
+
+    // The function that will run out the combo window and show the multiplier
+    fn combo_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // For every entity that qualify from the query "Score_Update" will be processed
+        for entity in query.iter("Score_Update") {
+            // Count down the combo window by the ticks of this frame, and break the chain once it runs out
+            if self.ticks > 0 {
+                let elapsed = self.ticks as f32 * FIXED_TIMESTEP;
+                query.modify::<Score>(entity, |value| {
+                    value.combo_timer = (value.combo_timer - elapsed).max(0.0);
+                    if value.combo_timer == 0.0 {
+                        value.multiplier = 1;
+                    }
+                });
+            }
+
+            // Show the multiplier while there is a combo chain going
+            let multiplier = query.read::<Score>(entity).multiplier;
+            let text = if multiplier > 1 {
+                format!("COMBO X{}", multiplier)
+            } else {
+                String::new()
+            };
+            for label in query.iter("Combo_Label") {
+                if query.read::<TextLabel>(label).characters() != text.as_bytes() {
+                    query.modify::<TextLabel>(label, |value| value.set_text(&text));
+                }
+            }
+        }
+    }
+
+    // The function that will end the game when the player is destroyed with no ships left, give the bonus ships,
+    // and move on to the next stage from the kills
+    fn game_state_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Read the score for the bonus ships
+        let score = query
+            .iter("Score")
+            .map(|id| query.read::<Score>(id).score)
+            .next()
+            .unwrap_or(0);
+
+        // Count the time played by the ticks of this frame
+        let elapsed = self.ticks as f32 * FIXED_TIMESTEP;
+        if self.ticks > 0 {
+            for entity in query.iter("Game_State") {
+                query.modify::<GameState>(entity, |game| {
+                    if game.phase == GamePhase::Playing {
+                        game.stats.time += elapsed;
+                    }
+                });
+            }
+        }
+
+        // When a HitEvent event is received
+        for hit in io.inbox::<HitEvent>() {
+            // For every entity that qualify from the query "Game_State" will be processed
+            for entity in query.iter("Game_State") {
+                query.modify::<GameState>(entity, |game| {
+                    // Record the hit for the results screen
+                    if game.phase == GamePhase::Playing {
+                        game.stats.record(&hit);
+                    }
+
+                    match hit.target_kind {
+                        // Count the destroyed enemies, and move on to the next stage every STAGE_KILLS of them
+                        HitKind::Enemy(_) if hit.destroyed && game.phase == GamePhase::Playing => {
+                            game.kills += 1;
+                            game.stage = 1 + game.kills / STAGE_KILLS;
+                        }
+                        // When the player is destroyed, the next ship comes from the reserve
+                        HitKind::Player if hit.destroyed && game.lives > 0 => {
+                            game.lives -= 1;
+                        }
+                        // The game is over when the player is destroyed with no ships left; show the results first
+                        HitKind::Player if hit.destroyed => {
+                            game.phase = GamePhase::Results;
+                            game.results_timer = RESULTS_SCREEN_TIME;
+                        }
+                        _ => {}
+                    }
+                });
+            }
+        }
+
+        // Give a bonus ship for every threshold the score has passed on this game
+        let table = self.extra_life_table;
+        for entity in query.iter("Game_State") {
+            query.modify::<GameState>(entity, |game| {
+                let earned = table.earned(score);
+                if game.phase == GamePhase::Playing && earned > game.extra_lives {
+                    game.lives += earned - game.extra_lives;
+                    game.extra_lives = earned;
+                    game.extra_life_notice = EXTRA_LIFE_NOTICE_TIME;
+                }
+            });
+        }
+    }
+
+    // The function that will show the ships in reserve and the bonus ship notification
+    fn lives_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Read the game state (with no game state, there is nothing to show)
+        let Some(entity) = query.iter("Game_State").next() else { return };
+
+        // Count down the bonus ship notification by the ticks of this frame
+        if self.ticks > 0 {
+            let elapsed = self.ticks as f32 * FIXED_TIMESTEP;
+            query.modify::<GameState>(entity, |game| {
+                game.extra_life_notice = (game.extra_life_notice - elapsed).max(0.0);
+            });
+        }
+        let game = query.read::<GameState>(entity);
+
+        // Blink the notification while it is shown
+        let text = if game.extra_life_notice > 0.0 && (game.extra_life_notice * 4.) as u32 % 2 == 0
+        {
+            "EXTRA SHIP"
+        } else {
+            ""
+        };
+        for label in query.iter("Extra_Life_Label") {
+            if query.read::<TextLabel>(label).characters() != text.as_bytes() {
+                query.modify::<TextLabel>(label, |value| value.set_text(text));
+            }
+        }
+
+        // Show one icon for every ship in reserve, from the bottom left corner of the window
+        update_hud_icons(
+            io,
+            query,
+            self.playfield.hud_anchor(HudRegion::ReserveShips),
+            HudRegion::ReserveShips,
+            game.lives.min(RESERVE_SHIP_MAX),
+            RESERVE_SHIP_HANDLE,
+            Vec3::new(RESERVE_SHIP_SPACING, 0., 0.),
+        );
     }
 
-    // The function that will handle the player fire
-    fn player_fire_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // If the FireCommand event is received from the client
-        if let Some(FireCommand(_value)) = io.inbox_first() {
-            // For every entity that qualify from the query "Player_Fire_Input" will be processed
-            for entity in query.iter("Player_Fire_Input") {
-                // Create the bullet entity from the plauyer position (the left bullet)
-                io.create_entity()
-                    // Add the render component as triangle
-                    .add_component(
-                        Render::new(PLAYER_BULLET_HANDLE).primitive(Primitive::Triangles),
-                    )
-                    // Add the synchronized component
-                    .add_component(Synchronized)
-                    // Add the bullet component that is from the player and from which entity is from (player entity in this case)
-                    .add_component(Bullet {
-                        from_enemy: false,
-                        from_player: true,
-                        entity_id: entity,
-                    })
-                    // Add the transform component with the position based on the player current position + top left
-                    .add_component(Transform::default().with_position(
-                        query.read::<Player>(entity).current_position
-                            + Vec3::new(-PLAYER_SIZE / 2., PLAYER_SIZE / 2., 0.0),
-                    ))
-                    // Build the entity
-                    .build();
-
-                // Create the bullet entity from the plauyer position (the right bullet)
-                io.create_entity()
-                    // Add the render component as triangle
-                    .add_component(
-                        Render::new(PLAYER_BULLET_HANDLE).primitive(Primitive::Triangles),
-                    )
-                    // Add the synchronized component
-                    .add_component(Synchronized)
-                    // Add the bullet component that is from the player and from which entity is from (player entity in this case)
-                    .add_component(Bullet {
-                        from_enemy: false,
-                        from_player: true,
-                        entity_id: entity,
-                    })
-                    // Add the transform component with the position based on the player current position + top right
-                    .add_component(Transform::default().with_position(
-                        query.read::<Player>(entity).current_position
-                            + Vec3::new(PLAYER_SIZE / 2., PLAYER_SIZE / 2., 0.0),
-                    ))
-                    // Build the entity
-                    .build();
+    // The function that will show one flag for every stage reached, from the bottom right corner of the window
+    fn stage_flag_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Read the game state (with no game state, there is nothing to show)
+        let Some(game) = query
+            .iter("Game_State")
+            .map(|id| query.read::<GameState>(id))
+            .next()
+        else { return };
+
+        update_hud_icons(
+            io,
+            query,
+            self.playfield.hud_anchor(HudRegion::StageFlags),
+            HudRegion::StageFlags,
+            game.stage.min(STAGE_FLAG_MAX),
+            STAGE_FLAG_HANDLE,
+            Vec3::new(-STAGE_FLAG_SPACING, 0., 0.),
+        );
+    }
+
+    // The function that will handle the stick/keys and fire on the game over and attract screens
+    fn game_input_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // Read which way the stick/keys are held and whether fire is held on this frame
+        let direction: f32 = io
+            .inbox::<MoveCommand>()
+            .map(|MoveCommand(command)| command.x)
+            .sum();
+        let fire_held = io.inbox::<FireCommand>().count() > 0;
+        let move_held = direction != 0.0;
+
+        // Only fresh presses count, so that holding does not run through the letters
+        let move_pressed = move_held && !self.menu_move_held;
+        let fire_pressed = fire_held && !self.menu_fire_held;
+        self.menu_move_held = move_held;
+        self.menu_fire_held = fire_held;
+
+        // For every entity that qualify from the query "Game_State" will be processed
+        for entity in query.iter("Game_State") {
+            let mut game = query.read::<GameState>(entity);
+            match game.phase {
+                // Show the results until fire is pressed or the time runs out, then enter the initials if the score is on the table
+                GamePhase::Results => {
+                    game.results_timer -= self.ticks as f32 * FIXED_TIMESTEP;
+                    if fire_pressed || game.results_timer <= 0.0 {
+                        let score = query
+                            .iter("Score_Update")
+                            .map(|id| query.read::<Score>(id).score)
+                            .next()
+                            .unwrap_or(0);
+                        let qualifies = query
+                            .iter("High_Score_Table")
+                            .any(|id| query.read::<HighScoreTable>(id).qualifies(score));
+                        if qualifies {
+                            game.phase = GamePhase::EnterInitials;
+                            game.initials = [b'A'; 3];
+                            game.cursor = 0;
+                        } else {
+                            game.phase = GamePhase::Attract;
+                        }
+                    }
+                }
+                // Change the letter with left/right, and go to the next letter with fire
+                GamePhase::EnterInitials => {
+                    let letter = &mut game.initials[game.cursor as usize];
+                    if move_pressed {
+                        let step = if direction > 0.0 { 1 } else { 25 };
+                        *letter = b'A' + (*letter - b'A' + step) % 26;
+                    }
+                    if fire_pressed {
+                        game.cursor += 1;
+                    }
+
+                    // Once all three letters are in, put the score on the table and show it
+                    if game.cursor as usize == game.initials.len() {
+                        let entry = HighScoreEntry {
+                            initials: game.initials,
+                            score: query
+                                .iter("Score_Update")
+                                .map(|id| query.read::<Score>(id).score)
+                                .next()
+                                .unwrap_or(0),
+                            stage: game.stage,
+                        };
+                        for table in query.iter("High_Score_Table") {
+                            query.modify::<HighScoreTable>(table, |value| value.insert(entry));
+                        }
+                        game.phase = GamePhase::Attract;
+                    }
+                }
+                // Start a new game with fire
+                GamePhase::Attract => {
+                    if fire_pressed {
+                        game = GameState::default();
+                        for score in query.iter("Score_Update") {
+                            query.modify::<Score>(score, |value| {
+                                *value = Score::default();
+                            });
+                        }
+                    }
+                }
+                GamePhase::Playing => {}
             }
+            query.modify::<GameState>(entity, |value| {
+                *value = game;
+            });
         }
     }
 
-    // The function that will handle the player bullet movement
-    fn player_bullet_movement_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // Get the FrameTime event
-        let Some(frame_time) = io.inbox_first::<FrameTime>() else { return };
+    // The function that will show the text of the game over and attract screens
+    fn game_screen_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        let Some(game) = query
+            .iter("Game_State")
+            .map(|id| query.read::<GameState>(id))
+            .next()
+        else { return };
+
+        // When the phase changes, remove the text of the last screen and create the text of the new one
+        if self.screen_phase != Some(game.phase) {
+            for label in self.screen_labels.drain(..) {
+                io.remove_entity(label);
+            }
 
-        // For every entity that qualify from the query "Player_Bullet_Movement" will be processed
-        for entity in query.iter("Player_Bullet_Movement") {
-            // If the bullet is from the player
-            if query.read::<Bullet>(entity).from_player {
-                // If the bullet is out of bound
-                if query.read::<Transform>(entity).pos.y > HEIGHT / 2. - 2.5 {
-                    // Remove the bullet entity
-                    io.remove_entity(entity);
+            match game.phase {
+                GamePhase::Playing => {}
+                GamePhase::Results => {
+                    // Show the results from below the top HUD regions
+                    let top =
+                        self.playfield.hud_anchor(HudRegion::TopScore).y - 3. * HUD_LINE_SPACING;
+                    let stats = game.stats;
+                    let rows = [
+                        format!("SHOTS FIRED {:>10}", stats.shots),
+                        format!("NUMBER OF HITS {:>7}", stats.hits),
+                        format!("HIT-MISS RATIO {:>6.1}%", stats.hit_ratio()),
+                        String::new(),
+                        format!("BEES {:>17}", stats.kills[EnemyKind::Bee as usize]),
+                        format!(
+                            "BUTTERFLIES {:>10}",
+                            stats.kills[EnemyKind::Butterfly as usize]
+                        ),
+                        format!("BOSSES {:>15}", stats.kills[EnemyKind::Boss as usize]),
+                        String::new(),
+                        format!("STAGE REACHED {:>8}", game.stage),
+                        format!(
+                            "TIME SURVIVED {:>5}:{:02}",
+                            stats.time as u32 / 60,
+                            stats.time as u32 % 60
+                        ),
+                    ];
+                    self.screen_labels = vec![create_label(
+                        io,
+                        "- RESULTS -",
+                        SCORE_LABEL_SIZE,
+                        Vec3::new(0., top, 0.),
+                    )];
+                    for (row, text) in rows.iter().enumerate() {
+                        let position =
+                            Vec3::new(0., top - 7. - row as f32 * HIGH_SCORE_ROW_SPACING, 0.);
+                        self.screen_labels.push(create_label(
+                            io,
+                            text,
+                            HIGH_SCORE_LABEL_SIZE,
+                            position,
+                        ));
+                    }
+                }
+                GamePhase::EnterInitials => {
+                    self.screen_labels = vec![
+                        create_label(io, "GAME OVER", SCORE_LABEL_SIZE, Vec3::new(0., 30., 0.)),
+                        create_label(
+                            io,
+                            "ENTER YOUR INITIALS",
+                            HIGH_SCORE_LABEL_SIZE,
+                            Vec3::new(0., 20., 0.),
+                        ),
+                        // The initials are filled in below while they are being entered
+                        create_label(io, "", SCORE_LABEL_SIZE, Vec3::new(0., -12., 0.)),
+                        create_label(
+                            io,
+                            "LEFT/RIGHT: LETTER  FIRE: NEXT",
+                            HIGH_SCORE_LABEL_SIZE,
+                            Vec3::new(0., -22., 0.),
+                        ),
+                    ];
+                }
+                GamePhase::Attract => {
+                    // Show the high score table from below the top HUD regions
+                    let top =
+                        self.playfield.hud_anchor(HudRegion::TopScore).y - 3. * HUD_LINE_SPACING;
+                    self.screen_labels = vec![create_label(
+                        io,
+                        "HIGH SCORES",
+                        SCORE_LABEL_SIZE,
+                        Vec3::new(0., top, 0.),
+                    )];
+                    let table = query
+                        .iter("High_Score_Table")
+                        .map(|id| query.read::<HighScoreTable>(id))
+                        .next()
+                        .unwrap_or_default();
+                    for (rank, entry) in table.entries().iter().enumerate() {
+                        let text = format!(
+                            "{:>2}. {} {:>7} STAGE {}",
+                            rank + 1,
+                            String::from_utf8_lossy(&entry.initials),
+                            entry.score,
+                            entry.stage
+                        );
+                        let position =
+                            Vec3::new(0., top - 7. - rank as f32 * HIGH_SCORE_ROW_SPACING, 0.);
+                        self.screen_labels.push(create_label(
+                            io,
+                            &text,
+                            HIGH_SCORE_LABEL_SIZE,
+                            position,
+                        ));
+                    }
+                    if table.entries().is_empty() {
+                        self.screen_labels.push(create_label(
+                            io,
+                            "NO HIGH SCORES YET",
+                            HIGH_SCORE_LABEL_SIZE,
+                            Vec3::new(0., top - 7., 0.),
+                        ));
+                    }
+                    self.screen_labels.push(create_label(
+                        io,
+                        "PRESS FIRE TO START",
+                        SCORE_LABEL_SIZE,
+                        Vec3::new(0., -20., 0.),
+                    ));
                 }
-                // Otherwise, move the bullet
-                query.modify::<Transform>(entity, |transform| {
-                    transform.pos +=
-                        Vec3::new(0.0, 1.0, 0.0) * frame_time.delta * PLAYER_BULLET_SPEED;
+            }
+            self.screen_phase = Some(game.phase);
+        }
+
+        // While the initials are being entered, show the letters so far with the one being changed
+        if game.phase == GamePhase::EnterInitials {
+            let text: String = game
+                .initials
+                .iter()
+                .enumerate()
+                .map(|(index, letter)| {
+                    if index <= game.cursor as usize {
+                        *letter as char
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            let label = self.screen_labels[2];
+            if query.iter("Screen_Label").any(|id| id == label) {
+                query.modify::<TextLabel>(label, |value| {
+                    if value.characters() != text.as_bytes() {
+                        value.set_text(&text);
+                    }
                 });
             }
         }
     }
 
-    // The function that will handle the enemy fire update
-    fn enemy_fire_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // Set the random generator for the enemy fire
-        let mut pcg_fire = Pcg::new();
+    // The function that will show the score on its number display
+    fn score_display(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // Keep the best score to show it next to the score
+        let mut high_score = query
+            .iter("High_Score_Table")
+            .filter_map(|id| {
+                query
+                    .read::<HighScoreTable>(id)
+                    .entries()
+                    .first()
+                    .map(|entry| entry.score)
+            })
+            .max()
+            .unwrap_or(0);
 
-        // For every entity that qualify from the query "Enemy_Fire_Input" will be processed
-        for entity in query.iter("Enemy_Fire_Input") {
-            // If the random generator return true to fire
-            if pcg_fire.gen_bool() {
-                // If the enemy bullet count is less than the max bullet count on screen from each enemy
-                if query.read::<Enemy>(entity).bullet_count < ENEMY_MAX_BULLET {
-                    // Increase the bullet count that are on screen from that enemy by 1
-                    query.modify::<Enemy>(entity, |value| {
-                        value.bullet_count += 1;
-                    });
-                    // Create the bullet entity from the enemy position
-                    io.create_entity()
+        // For every entity that qualify from the query "Score" will be processed
+        for entity in query.iter("Score") {
+            let score = query.read::<Score>(entity).score;
+            query.modify::<NumberDisplay>(entity, |display| {
+                display.value = score;
+            });
+            high_score = high_score.max(score);
+        }
+
+        // For every entity that qualify from the query "High_Score_Display" will be processed
+        for entity in query.iter("High_Score_Display") {
+            query.modify::<NumberDisplay>(entity, |display| {
+                display.value = high_score;
+            });
+        }
+    }
+
+    // The function that will draw the digits of every number display and the glyphs of every text label that changed
+    // (the glyph entities are kept, and only the ones that show a different character or moved are updated)
+    fn hud_text_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
+        // For every entity that qualify from the query "Number_Display" will be processed
+        for entity in query.iter("Number_Display") {
+            let mut display = query.read::<NumberDisplay>(entity);
+
+            // If the digits already show the value, there is nothing to draw
+            if display.shown == Some(display.value) {
+                continue;
+            }
+
+            let (digits, count) = display.digits();
+            let old_count = display.digit_count as usize;
+
+            // For every digit of the value
+            for (index, digit) in digits[..count].iter().enumerate() {
+                let position = display.digit_position(index, count);
+                let mesh = DIGIT_HANDLES[*digit as usize];
+
+                // If there is a glyph entity for this digit already, only update what changed on it
+                if index < old_count {
+                    let glyph = display.digit_entities[index];
+                    if display.shown_digits[index] != *digit {
+                        query.modify::<Render>(glyph, |render| {
+                            render.id = mesh;
+                        });
+                    }
+                    if query.read::<LocalTransform>(glyph).0.pos != position {
+                        query.modify::<LocalTransform>(glyph, |LocalTransform(transform)| {
+                            transform.pos = position;
+                        });
+                    }
+                }
+                // Otherwise, create the glyph entity for the new digit
+                else {
+                    display.digit_entities[index] = io
+                        .create_entity()
                         // Add the render component as triangle
-                        .add_component(
-                            Render::new(ENEMY_BULLET_HANDLE).primitive(Primitive::Triangles),
-                        )
+                        .add_component(Render::new(mesh).primitive(Primitive::Triangles))
                         // Add the synchronized component
                         .add_component(Synchronized)
-                        // Add the bullet component that is from the enemy and from which entity is from (enemy entity in this case)
-                        .add_component(Bullet {
-                            from_enemy: true,
-                            from_player: false,
-                            entity_id: entity,
-                        })
-                        // Add the transform component with the position based on the enemy current position + top (bottom based on player persepective)
-                        .add_component(Transform::default().with_position(
-                            query.read::<Enemy>(entity).current_position
-                                + Vec3::new(0., -ENEMY_SIZE / 2., 0.),
+                        // Add the glyph component so that the glyph is cleaned up with the display
+                        .add_component(Glyph { owner: entity })
+                        // Add the transform component with the position of the digit on the display
+                        .add_component(LocalTransform(
+                            Transform::default()
+                                .with_position(position)
+                                .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
                         ))
+                        // Add the world transform, which the playfield system places from the local transform
+                        .add_component(Transform::default())
                         // Build the entity
                         .build();
                 }
+                display.shown_digits[index] = *digit;
+            }
+
+            // Remove the glyph entities of the digits that are gone
+            for glyph in &display.digit_entities[count.min(old_count)..old_count] {
+                io.remove_entity(*glyph);
             }
+
+            // Remember what the glyph entities show now
+            display.digit_count = count as u32;
+            display.shown = Some(display.value);
+            query.modify::<NumberDisplay>(entity, |value| {
+                *value = display;
+            });
         }
-    }
 
-    // The function that will handle the enemy bullet movement
-    fn enemy_bullet_movement_update(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // Get the FrameTime event
-        if let Some(frame_time) = io.inbox_first::<FrameTime>() {
-            // For every entity that qualify from the query "Enemy_Bullet_Movement" will be processed
-            for entity in query.iter("Enemy_Bullet_Movement") {
-                // If the bullet is from the enemy
-                if query.read::<Bullet>(entity).from_enemy {
-                    // If the bullet is out of bound
-                    if query.read::<Transform>(entity).pos.y < -HEIGHT / 2. + 2.5 {
-                        // If that enemy entity exists on the screen from the query "Enemy_Bullet_Count_Update"
-                        if query
-                            .iter("Enemy_Bullet_Count_Update")
-                            .any(|id| id == query.read::<Bullet>(entity).entity_id)
-                        {
-                            // Decrease the bullet count that are on screen from that enemy by 1
-                            query.modify::<Enemy>(
-                                query.read::<Bullet>(entity).entity_id,
-                                |value| {
-                                    value.bullet_count -= 1;
-                                },
-                            );
-                        }
-                        // Remove the bullet entity
-                        io.remove_entity(entity);
+        // For every entity that qualify from the query "Text_Label" will be processed
+        for entity in query.iter("Text_Label") {
+            let mut label = query.read::<TextLabel>(entity);
+            let position = query.read::<LocalTransform>(entity).0.pos;
+            let old_count = label.glyph_count as usize;
+
+            // For every character of the text
+            let text = label.text;
+            for (index, character) in text[..label.length as usize].iter().enumerate() {
+                let glyph_position = position + label.glyph_offset(index);
+                let mesh = glyph_handle(*character, label.size, label.color);
+
+                // If there is a glyph entity for this character already, only update what changed on it
+                if index < old_count {
+                    let glyph = label.glyph_entities[index];
+                    if query.read::<Render>(glyph).id != mesh {
+                        query.modify::<Render>(glyph, |render| {
+                            render.id = mesh;
+                        });
                     }
-                    // Otherwise, move the bullet
-                    query.modify::<Transform>(entity, |transform| {
-                        transform.pos +=
-                            Vec3::new(0.0, -1.0, 0.0) * frame_time.delta * ENEMY_BULLET_SPEED;
-                    });
+                    if query.read::<LocalTransform>(glyph).0.pos != glyph_position {
+                        query.modify::<LocalTransform>(glyph, |LocalTransform(transform)| {
+                            transform.pos = glyph_position;
+                        });
+                    }
+                }
+                // Otherwise, create the glyph entity for the new character
+                else {
+                    label.glyph_entities[index] = io
+                        .create_entity()
+                        // Add the render component as lines
+                        .add_component(Render::new(mesh).primitive(Primitive::Lines))
+                        // Add the synchronized component
+                        .add_component(Synchronized)
+                        // Add the glyph component so that the glyph is cleaned up with the label
+                        .add_component(Glyph { owner: entity })
+                        // Add the transform component with the position of the character on the label
+                        .add_component(LocalTransform(
+                            Transform::default().with_position(glyph_position),
+                        ))
+                        // Add the world transform, which the playfield system places from the local transform
+                        .add_component(Transform::default())
+                        // Build the entity
+                        .build();
                 }
             }
+
+            // Remove the glyph entities of the characters that are gone
+            let count = label.length as usize;
+            for glyph in &label.glyph_entities[count.min(old_count)..old_count] {
+                io.remove_entity(*glyph);
+            }
+
+            // Remember how many glyph entities there are now
+            if label.glyph_count != label.length {
+                label.glyph_count = label.length;
+                query.modify::<TextLabel>(entity, |value| {
+                    *value = label;
+                });
+            }
         }
-    }
 
-    // The function that will handle the collision from player bullet to enemy
-    fn player_bullet_to_enemy_collision(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // For every entity that qualify from the query "Player_Bullet" will be processed
-        for entity1 in query.iter("Player_Bullet") {
-            // If the bullet is from the player
-            if query.read::<Bullet>(entity1).from_player {
-                // For every entity that qualify from the query "Enemy" will be processed
-                for entity2 in query.iter("Enemy") {
-                    // Get the current position of the bullet and the enemy
-                    let current_player_bullet = query.read::<Transform>(entity1).pos;
-                    let current_enemy = query.read::<Transform>(entity2).pos;
-
-                    // If the bullet hit the enemy
-                    if collision_detection(
-                        current_player_bullet.x,
-                        current_player_bullet.y,
-                        BULLET_SIZE,
-                        current_enemy.x,
-                        current_enemy.y,
-                        ENEMY_SIZE,
-                    ) {
-                        // Remove the bullet entity
-                        io.remove_entity(entity1);
-                        // Remove the enemy entity
-                        io.remove_entity(entity2);
-                        // For every entity that qualify from the query "Score_Update" will be processed
-                        for entity3 in query.iter("Score_Update") {
-                            // Increase the score by 1
-                            query.modify::<Score>(entity3, |value| {
-                                value.score += 1;
-                            });
-                        }
-                    }
-                }
+        // Remove the glyph entities whose display or label is gone
+        for glyph in query.iter("Glyph") {
+            let owner = query.read::<Glyph>(glyph).owner;
+            if !query.iter("Number_Display").any(|id| id == owner)
+                && !query.iter("Text_Label").any(|id| id == owner)
+            {
+                io.remove_entity(glyph);
             }
         }
     }
 
-    // The function that will handle the collision from enemy bullet to player
-    fn enemy_bullet_to_player_collision(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // For every entity that qualify from the query "Enemy_Bullet" will be processed
-        for entity1 in query.iter("Enemy_Bullet") {
-            // If the bullet is from the enemy
-            if query.read::<Bullet>(entity1).from_enemy {
-                // For every entity that qualify from the query "Player" will be processed
-                for entity2 in query.iter("Player") {
-                    // Get the current position of the bullet and the player
-                    let current_enemy_bullet = query.read::<Transform>(entity1).pos;
-                    let current_player = query.read::<Transform>(entity2).pos;
-
-                    // If the bullet hit the player
-                    if collision_detection(
-                        current_enemy_bullet.x,
-                        current_enemy_bullet.y,
-                        BULLET_SIZE,
-                        current_player.x,
-                        current_player.y,
-                        PLAYER_SIZE,
-                    ) {
-                        // Update the bullet count from that enemy to generate more bullets
-                        if query
-                            .iter("Enemy_Bullet_Count_Update")
-                            .any(|id| id == query.read::<Bullet>(entity1).entity_id)
-                        {
-                            query.modify::<Enemy>(
-                                query.read::<Bullet>(entity1).entity_id,
-                                |value| {
-                                    value.bullet_count -= 1;
-                                },
-                            );
-                        }
-                        // Remove the bullet entity
-                        io.remove_entity(entity1);
-                        // Remove the player entity
-                        io.remove_entity(entity2);
-                        // For every entity that qualify from the query "Player_Status_Update" will be processed
-                        for entity3 in query.iter("Player_Status_Update") {
-                            // Set the player status as dead
-                            query.modify::<PlayerStatus>(entity3, |value| {
-                                value.status = false;
-                            });
-                        }
-                        // For every entity that qualify from the query "Score_Update" will be processed
-                        for entity4 in query.iter("Score_Update") {
-                            // Reset the score to 0
-                            query.modify::<Score>(entity4, |value| {
-                                value.score = 0;
-                            });
-                        }
-                    }
-                }
+    // The function that will place every entity from the playfield into the world
+    fn playfield_update(&mut self, _io: &mut EngineIo, query: &mut QueryResult) {
+        // For every entity that qualify from the query "Playfield" will be processed
+        for entity in query.iter("Playfield") {
+            let LocalTransform(local) = query.read::<LocalTransform>(entity);
+            query.modify::<Transform>(entity, |transform| {
+                *transform = self.playfield.to_world(local);
+            });
+        }
+    }
+}
+
+// Uniform grid over the window used as the collision broadphase, so that only nearby colliders are checked
+struct SpatialGrid {
+    columns: usize,
+    rows: usize,
+    cell_size: f32,
+    // The bottom left corner of the grid
+    origin: Vec3,
+    // The indices of the colliders in each cell
+    cells: Vec<Vec<usize>>,
+}
+
+// Implement the grid functions
+impl SpatialGrid {
+    // Create an empty grid covering the window centered on the world origin
+    fn new(width: f32, height: f32, cell_size: f32) -> Self {
+        let columns = (width / cell_size).ceil().max(1.) as usize;
+        let rows = (height / cell_size).ceil().max(1.) as usize;
+
+        Self {
+            columns,
+            rows,
+            cell_size,
+            origin: Vec3::new(-width / 2., -height / 2., 0.),
+            cells: vec![Vec::new(); columns * rows],
+        }
+    }
+
+    // Get the cell column or row of a coordinate; anything outside the window goes into the border cells
+    fn cell(&self, coordinate: f32, origin: f32, count: usize) -> usize {
+        (((coordinate - origin) / self.cell_size).floor().max(0.) as usize).min(count - 1)
+    }
+
+    // Add the collider to every cell that its bounding box (from the bottom left min to the top right max) covers
+    fn insert(&mut self, index: usize, min: Vec3, max: Vec3) {
+        let min_column = self.cell(min.x, self.origin.x, self.columns);
+        let max_column = self.cell(max.x, self.origin.x, self.columns);
+        let min_row = self.cell(min.y, self.origin.y, self.rows);
+        let max_row = self.cell(max.y, self.origin.y, self.rows);
+
+        for row in min_row..=max_row {
+            for column in min_column..=max_column {
+                self.cells[row * self.columns + column].push(index);
             }
         }
     }
 
-    fn score_display(&mut self, io: &mut EngineIo, query: &mut QueryResult) {
-        // For every entity that qualify from the query "Score" will be processed
-        for entity in query.iter("Score") {
-            // The text handle list for the digit
-            let digit_list = [
-                ZERO_TEXT_HANDLE,
-                ONE_TEXT_HANDLE,
-                TWO_TEXT_HANDLE,
-                THREE_TEXT_HANDLE,
-                FOUR_TEXT_HANDLE,
-                FIVE_TEXT_HANDLE,
-                SIX_TEXT_HANDLE,
-                SEVEN_TEXT_HANDLE,
-                EIGHT_TEXT_HANDLE,
-                NINE_TEXT_HANDLE,
-            ];
-
-            // Fetch the current score based on the digit placements
-            let first_digit = (query.read::<Score>(entity).score % 10) as usize;
-            let second_digit = (query.read::<Score>(entity).score / 10) as usize;
-
-            // If there is a change in the display digit from the current score
-            if (query.read::<Score>(entity).first_digit != first_digit as u32)
-                || (query.read::<Score>(entity).second_digit != second_digit as u32)
-            {
-                // Remove the old digit entities
-                io.remove_entity(query.read::<Score>(entity).first_digit_entity);
-                io.remove_entity(query.read::<Score>(entity).second_digit_entity);
-
-                // Create the Second Digit Entity
-                let second_entity_id = io
-                    .create_entity()
-                    // Add the render component as triangle
-                    .add_component(
-                        Render::new(digit_list[second_digit]).primitive(Primitive::Triangles),
-                    )
-                    // Add the synchronized component
-                    .add_component(Synchronized)
-                    // Add the transform component with the position on left middle screen
-                    .add_component(
-                        Transform::default()
-                            .with_position(Vec3::new(-2.5, 0., 0.))
-                            .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
-                    )
-                    // Build the entity
-                    .build();
-
-                // Create the First Digit Entity
-                let first_entity_id = io
-                    .create_entity()
-                    // Add the render component as triangle
-                    .add_component(
-                        Render::new(digit_list[first_digit]).primitive(Primitive::Triangles),
-                    )
-                    // Add the synchronized component
-                    .add_component(Synchronized)
-                    // Add the transform component with the position on right middle screen
-                    .add_component(
-                        Transform::default()
-                            .with_position(Vec3::new(2.5, 0., 0.))
-                            .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.)),
-                    )
-                    // Build the entity
-                    .build();
-
-                // Update the score display values entities
-                query.modify::<Score>(entity, |value| {
-                    value.first_digit_entity = first_entity_id;
-                    value.second_digit_entity = second_entity_id;
-                });
+    // Get every pair of colliders that share at least one cell, once each and in the same order as checking every pair
+    fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for cell in &self.cells {
+            for (i, first) in cell.iter().enumerate() {
+                for second in &cell[i + 1..] {
+                    pairs.push((*first.min(second), *first.max(second)));
+                }
             }
         }
+
+        // Colliders that cover several cells would otherwise be paired once per shared cell
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
     }
 }
 
-// The function that will handle the collision detection
+// The function that will create a white text label entity centered on the position inside the playfield
+fn create_label(io: &mut EngineIo, text: &str, size: f32, position: Vec3) -> EntityId {
+    io.create_entity()
+        // Add the text label component with the text
+        .add_component(TextLabel::new(text, size, [1.; 3], TextAnchor::Center))
+        // Add the transform component with the position of the text
+        .add_component(LocalTransform(Transform::default().with_position(position)))
+        // Add the world transform, which the playfield system places from the local transform
+        .add_component(Transform::default())
+        // Add the synchronized component so that the client can send the glyph meshes
+        .add_component(Synchronized)
+        // Build the entity
+        .build()
+}
+
+// The function that will handle the collision detection between two hitboxes of any shape
 fn collision_detection(
-    obj1_x_position: f32,
-    obj1_y_position: f32,
-    obj1_size: f32,
-    obj2_x_position: f32,
-    obj2_y_position: f32,
-    obj2_size: f32,
+    obj1_position: Vec3,
+    obj1: &Collider,
+    obj2_position: Vec3,
+    obj2: &Collider,
 ) -> bool {
-    // If the object 1 is within the object 2 based on the sqaure hitbox intersection
-    if obj1_x_position - (obj1_size / 2.) <= obj2_x_position + (obj2_size / 2.)
-        && obj1_x_position + (obj1_size / 2.) >= obj2_x_position - (obj2_size / 2.)
-        && (obj1_y_position - (obj1_size / 2.) <= obj2_y_position + (obj2_size / 2.))
-        && (obj1_y_position + (obj1_size / 2.) >= obj2_y_position - (obj2_size / 2.))
-    {
-        // Return true if the object 1 is within the object 2, or vice versa
+    // Every shape is a rectangle core with a round edge around it
+    let (obj1_half_size, obj1_radius) = obj1.rounded_box();
+    let (obj2_half_size, obj2_radius) = obj2.rounded_box();
+
+    // Find the gap between the two rectangle cores on the x and y axis (zero where they overlap)
+    let gap =
+        ((obj1_position - obj2_position).abs() - obj1_half_size - obj2_half_size).max(Vec3::ZERO);
+
+    // Return true if the cores are no further apart than the two round edges together
+    gap.x * gap.x + gap.y * gap.y <= (obj1_radius + obj2_radius) * (obj1_radius + obj2_radius)
+}
+
+// The function that will handle the swept collision detection, so that a fast object can not skip over a hitbox between two frames
+fn swept_collision_detection(
+    obj1_start: Vec3,
+    obj1_end: Vec3,
+    obj1: &Collider,
+    obj2_start: Vec3,
+    obj2_end: Vec3,
+    obj2: &Collider,
+) -> bool {
+    // Look at the movement of object 1 as if object 2 stood still at its start position
+    let relative_start = obj1_start - obj2_start;
+    let relative_movement = (obj1_end - obj1_start) - (obj2_end - obj2_start);
+
+    // The objects hit if that movement goes through the hitbox of object 2 grown by the hitbox of object 1 during this frame
+    // (growing a rounded rectangle by another one adds up both the cores and the round edges)
+    let (obj1_half_size, obj1_radius) = obj1.rounded_box();
+    let (obj2_half_size, obj2_radius) = obj2.rounded_box();
+    match ray_rounded_box_intersection(
+        relative_start,
+        relative_movement,
+        Vec3::ZERO,
+        obj1_half_size + obj2_half_size,
+        obj1_radius + obj2_radius,
+    ) {
+        Some(time) => time <= 1.0,
+        None => false,
+    }
+}
+
+// The function that will damage the enemy, and remove it if it was destroyed (returns true if it was destroyed;
+// the caller sends the HitEvent that the score is counted from)
+fn damage_enemy(io: &mut EngineIo, query: &mut QueryResult, enemy: EntityId, damage: f32) -> bool {
+    // Decrease the enemy health by the damage
+    query.modify::<Enemy>(enemy, |value| {
+        value.health -= damage;
+    });
+
+    // If the enemy has no health left
+    if query.read::<Enemy>(enemy).health <= 0.0 {
+        // Remove the enemy entity
+        io.remove_entity(enemy);
         return true;
     }
-    // Otherwise, return false
+    // Otherwise, the enemy is still alive
     return false;
 }
 
+// The function that will keep one icon entity for each of the count in the HUD region, each one step further from the anchor
+fn update_hud_icons(
+    io: &mut EngineIo,
+    query: &mut QueryResult,
+    anchor: Vec3,
+    region: HudRegion,
+    count: u32,
+    mesh: MeshHandle,
+    step: Vec3,
+) {
+    // Remove the icons of the region past the count
+    let mut icons = vec![false; count as usize];
+    for icon in query.iter("Hud_Icon") {
+        if query.read::<HudAnchor>(icon).region != region {
+            continue;
+        }
+        let index = query.read::<HudIcon>(icon).index;
+        if index < count && !icons[index as usize] {
+            icons[index as usize] = true;
+        } else {
+            io.remove_entity(icon);
+        }
+    }
+
+    // Add the missing icons
+    for index in 0..count {
+        if !icons[index as usize] {
+            let offset = step * index as f32;
+            io.create_entity()
+                .add_component(HudIcon { index })
+                .add_component(HudAnchor { region, offset })
+                .add_component(LocalTransform(
+                    Transform::default().with_position(anchor + offset),
+                ))
+                // Add the world transform, which the playfield system places from the local transform
+                .add_component(Transform::default())
+                .add_component(Render::new(mesh).primitive(Primitive::Lines))
+                .add_component(Synchronized)
+                .build();
+        }
+    }
+}
+
+// The function that will count the enemies from the enemy query that are diving as escorts of the boss
+fn count_escorts(query: &mut QueryResult, enemy_query: &str, boss: EntityId) -> usize {
+    query
+        .iter(enemy_query)
+        .filter(|&entity| {
+            let enemy = query.read::<Enemy>(entity);
+            entity != boss
+                && enemy.escorting
+                && enemy.leader == boss
+                && enemy.state == EnemyState::Diving
+        })
+        .count()
+}
+
+// The function that will find how far along the ray it hits the rectangle hitbox, if it hits at all
+fn ray_box_intersection(
+    origin: Vec3,
+    direction: Vec3,
+    box_position: Vec3,
+    box_size: Vec3,
+) -> Option<f32> {
+    // Get the bottom left and the top right corners of the hitbox
+    let min = box_position - box_size / 2.;
+    let max = box_position + box_size / 2.;
+
+    // Find where the ray enters and leaves the hitbox on the x and y axis (slab method)
+    let mut enter = f32::MIN;
+    let mut leave = f32::MAX;
+    for (origin, direction, min, max) in [
+        (origin.x, direction.x, min.x, max.x),
+        (origin.y, direction.y, min.y, max.y),
+    ] {
+        // If the ray is parallel to this axis, it only hits when it starts between the two sides
+        if direction == 0.0 {
+            if origin < min || origin > max {
+                return None;
+            }
+            continue;
+        }
+
+        let near = (min - origin) / direction;
+        let far = (max - origin) / direction;
+        enter = enter.max(near.min(far));
+        leave = leave.min(near.max(far));
+    }
+
+    // If the ray leaves before it enters, or the hitbox is behind the ray, there is no hit
+    if enter > leave || leave < 0.0 {
+        return None;
+    }
+
+    // Return the distance along the ray (zero if the ray starts inside the hitbox)
+    Some(enter.max(0.0))
+}
+
+// The function that will find how far along the ray it hits the rounded rectangle hitbox, if it hits at all
+fn ray_rounded_box_intersection(
+    origin: Vec3,
+    direction: Vec3,
+    box_position: Vec3,
+    box_half_size: Vec3,
+    radius: f32,
+) -> Option<f32> {
+    // A rounded rectangle is the core grown by the radius on the x axis, the core grown by the radius on the y axis,
+    // and a circle on every corner of the core; the ray hits it where it first hits any of them
+    let corner = |x: f32, y: f32| {
+        ray_circle_intersection(
+            origin,
+            direction,
+            box_position + box_half_size * Vec3::new(x, y, 0.),
+            radius,
+        )
+    };
+    [
+        ray_box_intersection(
+            origin,
+            direction,
+            box_position,
+            (box_half_size + Vec3::new(radius, 0., 0.)) * 2.,
+        ),
+        ray_box_intersection(
+            origin,
+            direction,
+            box_position,
+            (box_half_size + Vec3::new(0., radius, 0.)) * 2.,
+        ),
+        corner(-1., -1.),
+        corner(1., -1.),
+        corner(1., 1.),
+        corner(-1., 1.),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(f32::min)
+}
+
+// The function that will find how far along the ray it hits the circle, if it hits at all
+fn ray_circle_intersection(
+    origin: Vec3,
+    direction: Vec3,
+    center: Vec3,
+    radius: f32,
+) -> Option<f32> {
+    let offset = origin - center;
+
+    // If the ray starts inside the circle, it hits right away
+    let c = offset.length_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+
+    // Otherwise, solve where the ray crosses the edge of the circle
+    let a = direction.length_squared();
+    let b = offset.dot(direction);
+    let discriminant = b * b - a * c;
+
+    // If the ray does not move, moves away from the circle, or misses it, there is no hit
+    if a == 0.0 || b > 0.0 || discriminant < 0.0 {
+        return None;
+    }
+
+    // Return the distance along the ray where it first crosses the edge
+    Some((-b - discriminant.sqrt()) / a)
+}
+
+// The function that will remove the bullet and release it from the bullet count of the weapon that fired it
+fn remove_bullet(io: &mut EngineIo, query: &mut QueryResult, weapon_query: &str, bullet: EntityId) {
+    // Read which entity fired the bullet
+    let owner = query.read::<Bullet>(bullet).entity_id;
+
+    // If that entity still exists, decrease the bullet count that are on screen from its weapon by 1
+    if query.iter(weapon_query).any(|id| id == owner) {
+        query.modify::<Weapon>(owner, |weapon| {
+            weapon.bullet_count = weapon.bullet_count.saturating_sub(1);
+        });
+    }
+
+    // Remove the bullet entity
+    io.remove_entity(bullet);
+}
+
 // Defines entry points for the engine to hook into.
 // Calls new() for the appropriate state.
-make_app_state!(ClientState, ServerState);
\ No newline at end of file
+make_app_state!(ClientState, ServerState);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_life_thresholds() {
+        let table = ExtraLifeTable::default();
+        assert_eq!(table.threshold(0), Some(20_000));
+        assert_eq!(table.threshold(1), Some(90_000));
+        assert_eq!(table.threshold(2), Some(160_000));
+
+        // Only the first bonus ship when there is no repeat
+        let once = ExtraLifeTable {
+            first: 20_000,
+            every: 0,
+        };
+        assert_eq!(once.threshold(1), None);
+        assert_eq!(once.earned(1_000_000), 1);
+    }
+
+    #[test]
+    fn extra_lives_after_score_jump() {
+        let table = ExtraLifeTable::default();
+        assert_eq!(table.earned(19_999), 0);
+        assert_eq!(table.earned(20_000), 1);
+        assert_eq!(table.earned(89_999), 1);
+        // One big jump past several thresholds gives one ship for each of them
+        assert_eq!(table.earned(160_000), 3);
+        // The count agrees with the thresholds, even for the highest score
+        for score in [20_000, 90_000, 1_234_567, u32::MAX] {
+            let earned = table.earned(score);
+            assert!(table.threshold(earned - 1).unwrap() <= score);
+            assert!(!table.threshold(earned).is_some_and(|next| next <= score));
+        }
+        assert_eq!(table.earned(u32::MAX), 1 + (u32::MAX - 20_000) / 70_000);
+
+        // A ship for every point does not take longer to count
+        let every_point = ExtraLifeTable { first: 1, every: 1 };
+        assert_eq!(every_point.earned(u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn autofire_off_only_fires_on_presses() {
+        let mut fire = Autofire::new(false, 10.);
+        assert!(fire.update(true, 0.016));
+        // Holding does not fire again
+        assert!(!fire.update(true, 0.5));
+        assert!(!fire.update(true, 0.5));
+        // Releasing and pressing again fires
+        assert!(!fire.update(false, 0.016));
+        assert!(fire.update(true, 0.016));
+    }
+
+    #[test]
+    fn autofire_on_fires_at_the_rate() {
+        let mut fire = Autofire::new(true, 10.);
+        assert!(fire.update(true, 0.016));
+        assert!(!fire.update(true, 0.05));
+        assert!(fire.update(true, 0.06));
+        assert!(!fire.update(true, 0.05));
+        assert!(fire.update(true, 0.05));
+        // Nothing fires once released
+        assert!(!fire.update(false, 0.5));
+    }
+
+    // A small random generator so that the tests do not depend on the engine
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next_f32(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+
+        fn range(&mut self, min: f32, max: f32) -> f32 {
+            min + (max - min) * self.next_f32()
+        }
+    }
+
+    fn collider(shape: ColliderShape, size: Vec3) -> Collider {
+        Collider {
+            shape,
+            size,
+            offset: Vec3::ZERO,
+            layer: LAYER_PLAYER_BULLET,
+            mask: LAYER_ENEMY,
+        }
+    }
+
+    // Random colliders over the window and a bit past it, half of them snapped onto the cell borders
+    fn random_colliders(random: &mut Lcg, count: usize) -> Vec<(Vec3, Vec3, Collider)> {
+        let shapes = [
+            ColliderShape::Rectangle,
+            ColliderShape::Circle,
+            ColliderShape::Capsule,
+        ];
+        (0..count)
+            .map(|i| {
+                let mut position = Vec3::new(
+                    random.range(-WITDH / 2. - 5., WITDH / 2. + 5.),
+                    random.range(-HEIGHT / 2. - 5., HEIGHT / 2. + 5.),
+                    0.,
+                );
+                if i % 2 == 0 {
+                    position.x = (position.x / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
+                    position.y = (position.y / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
+                }
+                let movement = if i % 3 == 0 {
+                    Vec3::new(random.range(-5., 5.), random.range(-5., 5.), 0.)
+                } else {
+                    Vec3::ZERO
+                };
+                let size = Vec3::new(random.range(0.5, 12.), random.range(0.5, 12.), 0.);
+                (
+                    position - movement,
+                    position,
+                    collider(shapes[i % shapes.len()], size),
+                )
+            })
+            .collect()
+    }
+
+    // The same hit check as the collision system
+    fn hit(a: &(Vec3, Vec3, Collider), b: &(Vec3, Vec3, Collider)) -> bool {
+        if a.0 == a.1 && b.0 == b.1 {
+            collision_detection(a.1, &a.2, b.1, &b.2)
+        } else {
+            swept_collision_detection(a.0, a.1, &a.2, b.0, b.1, &b.2)
+        }
+    }
+
+    #[test]
+    fn spatial_grid_matches_brute_force() {
+        let mut random = Lcg(7);
+        for _ in 0..20 {
+            let colliders = random_colliders(&mut random, 150);
+
+            // Check every pair
+            let mut brute_force = Vec::new();
+            for i in 0..colliders.len() {
+                for j in i + 1..colliders.len() {
+                    if hit(&colliders[i], &colliders[j]) {
+                        brute_force.push((i, j));
+                    }
+                }
+            }
+
+            // Only check the pairs that share a grid cell, the same way as the collision system
+            let mut grid = SpatialGrid::new(WITDH, HEIGHT, GRID_CELL_SIZE);
+            for (i, (start, position, collider)) in colliders.iter().enumerate() {
+                let half_size = collider.size / 2.;
+                grid.insert(
+                    i,
+                    start.min(*position) - half_size,
+                    start.max(*position) + half_size,
+                );
+            }
+            let candidates = grid.candidate_pairs();
+            let from_grid: Vec<(usize, usize)> = candidates
+                .iter()
+                .copied()
+                .filter(|&(i, j)| hit(&colliders[i], &colliders[j]))
+                .collect();
+
+            assert!(!brute_force.is_empty());
+            assert_eq!(from_grid, brute_force);
+
+            // Every pair comes out once and in order
+            assert!(candidates.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(candidates.iter().all(|(i, j)| i < j));
+        }
+    }
+
+    #[test]
+    fn swept_collision_catches_fast_bullets() {
+        let bullet = collider(ColliderShape::Circle, Vec3::new(1., 1., 0.));
+        let enemy = collider(ColliderShape::Capsule, Vec3::new(4., 2., 0.));
+
+        // The bullet jumps over the enemy in one tick, so only the sweep sees the hit
+        let start = Vec3::new(0., -10., 0.);
+        let end = Vec3::new(0., 10., 0.);
+        assert!(!collision_detection(end, &bullet, Vec3::ZERO, &enemy));
+        assert!(swept_collision_detection(
+            start,
+            end,
+            &bullet,
+            Vec3::ZERO,
+            Vec3::ZERO,
+            &enemy
+        ));
+
+        // A path next to the enemy does not hit it
+        assert!(!swept_collision_detection(
+            Vec3::new(5., -10., 0.),
+            Vec3::new(5., 10., 0.),
+            &bullet,
+            Vec3::ZERO,
+            Vec3::ZERO,
+            &enemy
+        ));
+
+        // A path that stops before the enemy does not hit it
+        assert!(!swept_collision_detection(
+            start,
+            Vec3::new(0., -5., 0.),
+            &bullet,
+            Vec3::ZERO,
+            Vec3::ZERO,
+            &enemy
+        ));
+    }
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn ray_rounded_box_hits_the_side() {
+        let distance = ray_rounded_box_intersection(
+            Vec3::new(-10., 0., 0.),
+            Vec3::new(1., 0., 0.),
+            Vec3::ZERO,
+            Vec3::new(1., 1., 0.),
+            0.5,
+        );
+        assert!(approx(distance.unwrap(), 8.5));
+    }
+
+    #[test]
+    fn ray_rounded_box_corner_is_round() {
+        // A square box would be hit at 3, but the round corner is only reached later
+        let distance = ray_rounded_box_intersection(
+            Vec3::new(-5., -5., 0.),
+            Vec3::new(1., 1., 0.),
+            Vec3::ZERO,
+            Vec3::new(1., 1., 0.),
+            1.,
+        )
+        .unwrap();
+        assert!(approx(distance, 4. - 1. / 2f32.sqrt()));
+    }
+
+    #[test]
+    fn ray_rounded_box_misses() {
+        let distance = ray_rounded_box_intersection(
+            Vec3::new(-10., 5., 0.),
+            Vec3::new(1., 0., 0.),
+            Vec3::ZERO,
+            Vec3::new(1., 1., 0.),
+            0.5,
+        );
+        assert_eq!(distance, None);
+
+        // Going away from the box is not a hit either
+        let distance = ray_rounded_box_intersection(
+            Vec3::new(-10., 0., 0.),
+            Vec3::new(-1., 0., 0.),
+            Vec3::ZERO,
+            Vec3::new(1., 1., 0.),
+            0.5,
+        );
+        assert_eq!(distance, None);
+    }
+
+    fn digits(value: u32, min_digits: u32) -> Vec<u32> {
+        let mut display = NumberDisplay::new(Vec3::ZERO, TextAnchor::Center, 1., min_digits);
+        display.value = value;
+        let (digits, count) = display.digits();
+        digits[..count].to_vec()
+    }
+
+    #[test]
+    fn number_display_digits() {
+        assert_eq!(digits(0, 1), vec![0]);
+        assert_eq!(digits(7, 1), vec![7]);
+        assert_eq!(digits(12345, 1), vec![1, 2, 3, 4, 5]);
+        // Leading zeros up to the min digits
+        assert_eq!(digits(42, 6), vec![0, 0, 0, 0, 4, 2]);
+        assert_eq!(digits(0, 3), vec![0, 0, 0]);
+        // Every u32 value fits
+        assert_eq!(digits(u32::MAX, 1), vec![4, 2, 9, 4, 9, 6, 7, 2, 9, 5]);
+    }
+
+    fn entry(initial: u8, score: u32) -> HighScoreEntry {
+        HighScoreEntry {
+            initials: [initial; 3],
+            score,
+            stage: 1,
+        }
+    }
+
+    #[test]
+    fn high_score_table_keeps_the_order() {
+        let mut table = HighScoreTable::default();
+        table.insert(entry(b'A', 100));
+        table.insert(entry(b'B', 300));
+        table.insert(entry(b'C', 200));
+        // A tie goes below the score that was already there
+        table.insert(entry(b'D', 200));
+
+        let scores: Vec<(u8, u32)> = table
+            .entries()
+            .iter()
+            .map(|entry| (entry.initials[0], entry.score))
+            .collect();
+        assert_eq!(
+            scores,
+            vec![(b'B', 300), (b'C', 200), (b'D', 200), (b'A', 100)]
+        );
+    }
+
+    #[test]
+    fn high_score_table_drops_the_lowest() {
+        let mut table = HighScoreTable::default();
+        for score in 1..=12 {
+            table.insert(entry(b'A', score));
+        }
+
+        let scores: Vec<u32> = table.entries().iter().map(|entry| entry.score).collect();
+        assert_eq!(scores, (3..=12).rev().collect::<Vec<u32>>());
+
+        // A score below the whole full table does not get on it
+        assert!(!table.qualifies(3));
+        assert!(table.qualifies(4));
+        table.insert(entry(b'Z', 2));
+        assert_eq!(table.entries().len(), HIGH_SCORE_COUNT);
+        assert_eq!(table.entries()[HIGH_SCORE_COUNT - 1].score, 3);
+    }
+
+    fn enemy(kind: EnemyKind, state: EnemyState) -> Enemy {
+        Enemy {
+            state,
+            ..Enemy::new(kind)
+        }
+    }
+
+    #[test]
+    fn scoring_table_points_by_kind_and_state() {
+        let scoring = ScoringTable::default();
+        assert_eq!(
+            scoring.points(&enemy(EnemyKind::Bee, EnemyState::Formation), 0),
+            50
+        );
+        assert_eq!(
+            scoring.points(&enemy(EnemyKind::Butterfly, EnemyState::Formation), 0),
+            80
+        );
+        assert_eq!(
+            scoring.points(&enemy(EnemyKind::Boss, EnemyState::Formation), 0),
+            150
+        );
+        assert_eq!(
+            scoring.points(&enemy(EnemyKind::Bee, EnemyState::Diving), 0),
+            100
+        );
+        assert_eq!(
+            scoring.points(&enemy(EnemyKind::Butterfly, EnemyState::Diving), 0),
+            160
+        );
+
+        // The escorts only count for a diving boss
+        assert_eq!(
+            scoring.points(&enemy(EnemyKind::Butterfly, EnemyState::Diving), 2),
+            160
+        );
+        assert_eq!(
+            scoring.points(&enemy(EnemyKind::Boss, EnemyState::Formation), 2),
+            150
+        );
+    }
+
+    #[test]
+    fn scoring_table_boss_with_escorts() {
+        let scoring = ScoringTable::default();
+        let boss = enemy(EnemyKind::Boss, EnemyState::Diving);
+        assert_eq!(scoring.points(&boss, 0), 400);
+        assert_eq!(scoring.points(&boss, 1), 800);
+        assert_eq!(scoring.points(&boss, 2), 1600);
+        // More escorts than the table has are worth the most points
+        assert_eq!(scoring.points(&boss, 5), 1600);
+    }
+
+    fn hit_event(source_kind: HitKind, target_kind: HitKind, destroyed: bool) -> HitEvent {
+        HitEvent {
+            target: EntityId(1),
+            target_kind,
+            source: EntityId(2),
+            source_kind,
+            destroyed,
+            points: 0,
+            position: Vec3::ZERO,
+        }
+    }
+
+    #[test]
+    fn game_stats_record_player_hits() {
+        let mut stats = GameStats::default();
+        stats.record(&hit_event(
+            HitKind::PlayerBullet,
+            HitKind::Enemy(EnemyKind::Bee),
+            false,
+        ));
+        stats.record(&hit_event(
+            HitKind::PlayerBullet,
+            HitKind::Enemy(EnemyKind::Boss),
+            true,
+        ));
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.kills, [0, 0, 1]);
+
+        // The laser kills count, but the laser does not fire shots, so its hits do not
+        stats.record(&hit_event(
+            HitKind::PlayerLaser,
+            HitKind::Enemy(EnemyKind::Butterfly),
+            true,
+        ));
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.kills, [0, 1, 1]);
+
+        // The enemy hitting the player is not a player hit
+        stats.record(&hit_event(HitKind::EnemyBullet, HitKind::Player, true));
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.kills, [0, 1, 1]);
+    }
+
+    #[test]
+    fn game_stats_hit_ratio() {
+        let mut stats = GameStats::default();
+        // No shots is no hit ratio instead of a division by zero
+        assert_eq!(stats.hit_ratio(), 0.0);
+
+        stats.shots = 8;
+        stats.hits = 2;
+        assert_eq!(stats.hit_ratio(), 25.0);
+    }
+
+    #[test]
+    fn text_label_set_text() {
+        let mut label = TextLabel::new("Score: 10!", 1., [1.; 3], TextAnchor::Left);
+        assert_eq!(label.characters(), b"SCORE: 10!");
+
+        // The characters the font does not have are drawn as '?'
+        label.set_text("a~b\u{e9}");
+        assert_eq!(label.characters(), b"A?B?");
+
+        // The text is cut to the max length
+        label.set_text(&"X".repeat(40));
+        assert_eq!(label.characters().len(), TEXT_LABEL_MAX_LENGTH);
+        assert!(label
+            .characters()
+            .iter()
+            .all(|&character| character == b'X'));
+
+        // A shorter text does not keep the end of the longer one
+        label.set_text("OK");
+        assert_eq!(label.characters(), b"OK");
+    }
+
+    #[test]
+    fn text_label_glyph_offset() {
+        // With the size of the font grid, three glyphs are 2 advances and one glyph wide
+        let width = 2. * FONT_ADVANCE + FONT_WIDTH;
+        let label = |anchor| TextLabel::new("ABC", FONT_HEIGHT, [1.; 3], anchor);
+
+        let left = label(TextAnchor::Left);
+        assert_eq!(left.glyph_offset(0), Vec3::new(0., -FONT_HEIGHT / 2., 0.));
+        assert_eq!(
+            left.glyph_offset(1),
+            Vec3::new(FONT_ADVANCE, -FONT_HEIGHT / 2., 0.)
+        );
+
+        let center = label(TextAnchor::Center);
+        assert_eq!(center.glyph_offset(0).x, -width / 2.);
+        assert_eq!(center.glyph_offset(2).x, -width / 2. + 2. * FONT_ADVANCE);
+
+        let right = label(TextAnchor::Right);
+        assert_eq!(right.glyph_offset(0).x, -width);
+        // The last glyph ends on the label position
+        assert_eq!(right.glyph_offset(2).x + FONT_WIDTH, 0.);
+
+        // The size scales the whole text
+        let big = TextLabel::new("ABC", 2. * FONT_HEIGHT, [1.; 3], TextAnchor::Center);
+        assert_eq!(
+            big.glyph_offset(1),
+            Vec3::new(-width + 2. * FONT_ADVANCE, -FONT_HEIGHT, 0.)
+        );
+    }
+
+    #[test]
+    fn hitbox_from_rotated_mesh() {
+        // The obj files are drawn on the x/z plane, and the entities stand them up on the x/y plane
+        let rotation = Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.);
+        let mesh = Mesh {
+            vertices: vec![
+                Vertex::new([-2., 0., -1.], [1.; 3]),
+                Vertex::new([4., 0., 3.], [1.; 3]),
+            ],
+            indices: vec![0, 1],
+        };
+        let hitbox = Hitbox::from_mesh(&mesh, rotation, 0.5);
+        // Only the size is scaled, around the center of the bounds
+        assert!(hitbox.size.abs_diff_eq(Vec3::new(3., 2., 0.), 1e-4));
+        assert!(hitbox.offset.abs_diff_eq(Vec3::new(1., -1., 0.), 1e-4));
+
+        // The muzzles sit on the hitbox edges
+        assert!(hitbox.edge(0., 1.).abs_diff_eq(Vec3::new(1., 0., 0.), 1e-4));
+        assert!(hitbox
+            .edge(-1., -1.)
+            .abs_diff_eq(Vec3::new(-0.5, -2., 0.), 1e-4));
+    }
+
+    #[test]
+    fn hitbox_from_player_obj() {
+        let hitbox = Hitbox::from_mesh(
+            &obj_lines_to_mesh(&include_str!("assets/galagaship.obj")),
+            Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.),
+            PLAYER_HITBOX_SCALE,
+        );
+        // The ship is flat on the playfield, and about as wide as it is tall
+        assert!(hitbox.size.abs_diff_eq(
+            Vec3::new(4.697775, 5.004402, 0.) * PLAYER_HITBOX_SCALE,
+            1e-3
+        ));
+        assert!(hitbox
+            .offset
+            .abs_diff_eq(Vec3::new(0.008684, 0.004385, 0.), 1e-3));
+    }
+
+    #[test]
+    fn hitbox_from_empty_mesh() {
+        let mesh = Mesh {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        };
+        let hitbox = Hitbox::from_mesh(&mesh, Quat::IDENTITY, 1.);
+        assert_eq!(hitbox.size, Vec3::ZERO);
+        assert_eq!(hitbox.offset, Vec3::ZERO);
+    }
+
+    #[test]
+    fn playfield_to_world_rotates_and_scales() {
+        let playfield = Playfield {
+            transform: Transform {
+                pos: Vec3::new(10., 0., -5.),
+                orient: Quat::from_rotation_z(PI / 2.),
+            },
+            size: Vec3::new(WITDH, HEIGHT, 0.),
+            scale: 2.,
+        };
+        let local = Transform {
+            pos: Vec3::new(1., 2., 0.),
+            orient: Quat::from_rotation_x(0.3),
+        };
+
+        // The position is scaled, then turned with the playfield, then moved to the playfield position
+        let world = playfield.to_world(local);
+        assert!(world.pos.abs_diff_eq(Vec3::new(6., 2., -5.), 1e-4));
+        assert!(world.orient.abs_diff_eq(
+            Quat::from_rotation_z(PI / 2.) * Quat::from_rotation_x(0.3),
+            1e-4
+        ));
+
+        // The playfield origin is the playfield position
+        let origin = playfield.to_world(Transform::default());
+        assert!(origin.pos.abs_diff_eq(playfield.transform.pos, 1e-4));
+    }
+
+    #[test]
+    fn playfield_scales_meshes_like_positions() {
+        let playfield = Playfield {
+            scale: 0.5,
+            ..PLAYFIELD
+        };
+        let mesh = playfield.scale_mesh(Mesh {
+            vertices: vec![Vertex::new([2., -4., 6.], [1.; 3])],
+            indices: vec![0],
+        });
+        assert_eq!(mesh.vertices[0].pos, [1., -2., 3.]);
+
+        // A vertex drawn at a local position ends up where that position is placed in the world
+        let local = Vec3::new(2., -4., 0.);
+        let world = playfield.to_world(Transform::default().with_position(local));
+        assert!(world.pos.abs_diff_eq(
+            playfield.transform.pos + playfield.transform.orient * (local * 0.5),
+            1e-4
+        ));
+    }
+}
\ No newline at end of file